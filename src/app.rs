@@ -1,25 +1,98 @@
-use crate::{tui, words::WordFinder};
+use crate::{
+    export,
+    expr::ExprRowKind,
+    lists::{self, ListFile, LISTS_DIR},
+    ord::WordOrder,
+    preset::{self, Preset, PRESETS_PATH},
+    query::QueryError,
+    session::{self, Session, SESSION_PATH},
+    task::Task,
+    tui,
+    words::{LoadError, LoadStats, WordFinder, DEFAULT_FILE_PATH},
+};
 use ratatui::{
     crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
     prelude::*,
     text::ToLine,
     widgets::*,
 };
-use std::io;
+use std::{io, time::Duration};
 
 // =============================================================================
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct App {
     exit: bool,
     finder: WordFinder,
     state: State,
 }
 
+impl Default for App {
+    fn default() -> Self {
+        let mut app = Self {
+            exit: false,
+            finder: WordFinder::default(),
+            state: State::default(),
+        };
+
+        app.load_file(DEFAULT_FILE_PATH);
+
+        app
+    }
+}
+
 impl App {
+    /// picks up where the last session left off, or starts fresh if there isn't one
+    pub fn new() -> Self {
+        match session::load_session(SESSION_PATH) {
+            Ok(Some(session)) => Self::from_session(session),
+            Ok(None) => Self::default(),
+            Err(e) => {
+                let mut app = Self::default();
+                app.state.session_error = Some(format!("ignoring saved session, {}", e));
+                app
+            }
+        }
+    }
+
+    fn from_session(session: Session) -> Self {
+        let mut app = Self {
+            exit: false,
+            finder: WordFinder::default(),
+            state: State::default(),
+        };
+
+        if let Err(e) = session.preset.apply(&mut app.finder) {
+            app.state.session_error = Some(format!("ignoring saved predicates, {}", e));
+        }
+        app.state.focus_pane = SelectableArea::from_name(&session.pane).unwrap_or_default();
+
+        // falls back to the default list if the old one moved, see `handle_loaded`
+        if session.preset.file_path.is_empty() {
+            app.load_file(DEFAULT_FILE_PATH);
+        } else {
+            app.load_file(&session.preset.file_path);
+        }
+
+        app
+    }
+
+    /// for the next launch, called once the terminal is back to normal so any error can be shown
+    pub fn save_session(&self) -> Result<(), String> {
+        let session = Session {
+            preset: Preset::from_finder("session", &self.finder),
+            pane: self.state.focus_pane.name().to_string(),
+        };
+        session::save_session(SESSION_PATH, &session)
+    }
+
     /// runs the application's main loop until the user quits
     pub fn run(&mut self, terminal: &mut tui::Tui) -> io::Result<()> {
         while !self.exit {
+            if let Some(result) = self.finder.poll() {
+                self.handle_loaded(result);
+            }
+            self.poll_list_scan();
             terminal.draw(|frame| self.render_frame(frame))?;
             self.handle_events()?;
         }
@@ -31,6 +104,12 @@ impl App {
     }
 
     fn handle_events(&mut self) -> io::Result<()> {
+        // while loading or filtering, check back regularly to show how it's going
+        let busy = self.finder.is_busy() || self.state.list_scan.is_some();
+        if busy && !event::poll(Duration::from_millis(50))? {
+            return Ok(());
+        }
+
         match event::read()? {
             // it's important to check that the event is a key press event as
             // crossterm also emits key release and repeat events on Windows.
@@ -51,9 +130,36 @@ struct State {
     sort_list: ListState,
     pred_list: ListState,
     new_pred_list: ListState,
+    /// path of the group the new predicate popup adds to
+    new_pred_group: Vec<usize>,
     focus_pane: SelectableArea,
     input_mode: InputMode,
     insert_buf: String,
+    /// how the last attempt to load a word list went
+    load_status: Option<Result<LoadStats, LoadError>>,
+    /// the list from the last session didn't load and the default one is loading instead
+    falling_back: bool,
+    /// the path of the list being loaded
+    loading_path: String,
+    /// why the query being edited can't be used, if it can't
+    query_error: Option<QueryError>,
+    /// the query from before editing started, put back if editing is cancelled
+    query_before: String,
+    /// what couldn't be restored from the last session, shown until a key is pressed
+    session_error: Option<String>,
+    /// presets as they were in the file when the picker was opened
+    presets: Vec<Preset>,
+    preset_list: ListState,
+    preset_error: Option<String>,
+    /// where the results were last exported to, and how that went
+    export_path: String,
+    export_status: Option<Result<String, String>>,
+    /// the files in the lists folder when the browser was opened
+    list_files: Vec<ListFile>,
+    list_browser: ListState,
+    list_error: Option<String>,
+    /// reading through the lists folder, which takes a while with big lists
+    list_scan: Option<Task<Result<Vec<ListFile>, String>>>,
 }
 
 impl Default for State {
@@ -63,9 +169,25 @@ impl Default for State {
             sort_list: Default::default(),
             pred_list: Default::default(),
             new_pred_list: Default::default(),
+            new_pred_group: Default::default(),
             focus_pane: Default::default(),
             input_mode: Default::default(),
             insert_buf: Default::default(),
+            load_status: Default::default(),
+            falling_back: Default::default(),
+            loading_path: Default::default(),
+            query_error: Default::default(),
+            query_before: Default::default(),
+            session_error: Default::default(),
+            presets: Default::default(),
+            preset_list: Default::default(),
+            preset_error: Default::default(),
+            export_path: "./export.txt".to_string(),
+            export_status: Default::default(),
+            list_files: Default::default(),
+            list_browser: Default::default(),
+            list_error: Default::default(),
+            list_scan: Default::default(),
         };
 
         state.word_list.select(Some(0));
@@ -86,6 +208,31 @@ enum SelectableArea {
     NewPredicate,
     Sorting,
     File,
+    Query,
+    Presets,
+    PresetName,
+    Export,
+    Lists,
+}
+
+impl SelectableArea {
+    /// how the pane is written in the session file, popups and inputs count as the predicates
+    fn name(&self) -> &'static str {
+        match self {
+            SelectableArea::Words => "words",
+            SelectableArea::Sorting => "sorting",
+            _ => "predicates",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "words" => Some(SelectableArea::Words),
+            "sorting" => Some(SelectableArea::Sorting),
+            "predicates" => Some(SelectableArea::Predicates),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Default, PartialEq)]
@@ -97,6 +244,7 @@ enum InputMode {
 
 impl App {
     fn handle_key_event(&mut self, key_event: KeyEvent) {
+        self.state.session_error = None;
         match self.state.input_mode {
             InputMode::Normal => self.handle_normal_mode(key_event),
             InputMode::Insert => self.handle_insert_mode(key_event),
@@ -113,6 +261,19 @@ impl App {
             KeyCode::Enter => self.handle_enter(),
             KeyCode::Delete => self.handle_delete(),
             KeyCode::Char('f') => self.handle_edit_file(),
+            KeyCode::Char('/') => self.handle_edit_query(),
+            KeyCode::Char('n') => self.handle_negate(),
+            KeyCode::Char('r') => self.handle_reroll(),
+            KeyCode::Char('s') => self.handle_edit_seed(),
+            KeyCode::Char('t') => self.handle_add_order(),
+            KeyCode::Char('p') => self.open_presets(),
+            KeyCode::Char('w') => self.handle_name_preset(),
+            KeyCode::Char('e') => self.handle_edit_export(),
+            KeyCode::Char('l') => self.open_lists(),
+            KeyCode::Char('+') => self.combine_list(lists::ListOp::Union),
+            KeyCode::Char('&') => self.combine_list(lists::ListOp::Intersection),
+            KeyCode::Char('-') => self.combine_list(lists::ListOp::Difference),
+            KeyCode::Esc => self.handle_escape(),
             _ => {}
         }
     }
@@ -122,8 +283,37 @@ impl App {
             KeyCode::Char(c) => {
                 self.state.insert_buf.push(c);
             }
+            // a query that doesn't parse has to be fixed or given up on
+            KeyCode::Enter if self.state.query_error.is_some() => return,
+            KeyCode::Esc if self.state.focus_pane == SelectableArea::Query => {
+                self.state.input_mode = InputMode::Normal;
+                self.state.focus_pane = SelectableArea::Predicates;
+                self.state.query_error = None;
+                let before = std::mem::take(&mut self.state.query_before);
+                // it parsed before, so this can't fail
+                let _ = self.finder.set_query(&before);
+                return;
+            }
             KeyCode::Enter => {
                 self.state.input_mode = InputMode::Normal;
+                if self.state.focus_pane == SelectableArea::Query {
+                    // the query has already been applied while typing
+                    self.state.focus_pane = SelectableArea::Predicates;
+                    return;
+                }
+                if self.state.focus_pane == SelectableArea::PresetName {
+                    self.save_preset();
+                    return;
+                }
+                if self.state.focus_pane == SelectableArea::Export {
+                    self.export();
+                    return;
+                }
+                if self.state.focus_pane == SelectableArea::Sorting {
+                    // shuffling the whole list on every digit would be wasted
+                    self.update_seed();
+                    return;
+                }
             }
             KeyCode::Backspace => {
                 self.state.insert_buf.pop();
@@ -131,8 +321,10 @@ impl App {
             _ => {}
         }
 
-        if self.state.focus_pane == SelectableArea::Predicates {
-            self.update_predicate();
+        match self.state.focus_pane {
+            SelectableArea::Predicates => self.update_predicate(),
+            SelectableArea::Query => self.update_query(),
+            _ => {}
         }
     }
 
@@ -166,6 +358,8 @@ impl App {
             SelectableArea::Sorting => self.state.sort_list.select_next(),
             SelectableArea::Words => self.state.word_list.select_next(),
             SelectableArea::NewPredicate => self.state.new_pred_list.select_next(),
+            SelectableArea::Presets => self.state.preset_list.select_next(),
+            SelectableArea::Lists => self.state.list_browser.select_next(),
             _ => {}
         }
     }
@@ -180,6 +374,8 @@ impl App {
             SelectableArea::Sorting => self.state.sort_list.select_previous(),
             SelectableArea::Words => self.state.word_list.select_previous(),
             SelectableArea::NewPredicate => self.state.new_pred_list.select_previous(),
+            SelectableArea::Presets => self.state.preset_list.select_previous(),
+            SelectableArea::Lists => self.state.list_browser.select_previous(),
             _ => {}
         }
     }
@@ -192,12 +388,25 @@ impl App {
                     .pred_list
                     .selected()
                     .expect("Failed to get selected predicate");
-                if selected_index == self.finder.predicates.len() {
-                    self.state.focus_pane = SelectableArea::NewPredicate;
-                    self.state.new_pred_list.select(Some(0))
-                } else {
-                    self.state.insert_buf = self.finder.get_predicate_string(selected_index);
-                    self.state.input_mode = InputMode::Insert;
+                let rows = self.finder.predicate_rows();
+                let Some(row) = rows.get(selected_index) else {
+                    return;
+                };
+                let path = row.path.clone();
+
+                match row.kind {
+                    ExprRowKind::New => {
+                        self.state.new_pred_group = path;
+                        self.state.focus_pane = SelectableArea::NewPredicate;
+                        self.state.new_pred_list.select(Some(0))
+                    }
+                    ExprRowKind::AnyOf | ExprRowKind::AllOf => {
+                        self.finder.toggle_group(&path);
+                    }
+                    ExprRowKind::Pred(_) => {
+                        self.state.insert_buf = self.finder.get_predicate_string(&path);
+                        self.state.input_mode = InputMode::Insert;
+                    }
                 }
             }
             SelectableArea::NewPredicate => {
@@ -206,12 +415,20 @@ impl App {
                     .new_pred_list
                     .selected()
                     .expect("Failed to get selected new predicate");
-                self.finder.add_predicate(selected_index);
+                self.finder
+                    .add_predicate(&self.state.new_pred_group, selected_index);
                 self.state.focus_pane = SelectableArea::Predicates;
 
-                // immediately being editing the new predicate
-                self.state.insert_buf = String::new();
-                self.state.input_mode = InputMode::Insert;
+                // the new entry takes the place of the "+ New Predicate" row that was selected, so
+                // immediately begin editing it unless it's a group
+                let rows = self.finder.predicate_rows();
+                let pred_index = self.state.pred_list.selected();
+                if let Some(ExprRowKind::Pred(_)) =
+                    pred_index.and_then(|i| rows.get(i)).map(|r| &r.kind)
+                {
+                    self.state.insert_buf = String::new();
+                    self.state.input_mode = InputMode::Insert;
+                }
             }
             SelectableArea::Sorting => {
                 let selected_index = self
@@ -223,25 +440,270 @@ impl App {
                 self.finder.sort();
             }
             SelectableArea::File => {
-                self.finder.load_file(&self.state.insert_buf);
+                let path = std::mem::take(&mut self.state.insert_buf);
+                self.load_file(&path);
                 self.state.focus_pane = SelectableArea::Words;
                 self.state.input_mode = InputMode::Normal;
             }
+            SelectableArea::Presets => self.apply_preset(),
+            SelectableArea::Lists => self.load_selected_list(),
             _ => {}
         }
     }
 
     fn handle_delete(&mut self) {
-        if SelectableArea::Predicates == self.state.focus_pane {
+        match self.state.focus_pane {
+            SelectableArea::Predicates => {
+                if let Some(path) = self.selected_predicate_path() {
+                    self.finder.remove_predicate(&path);
+                }
+            }
+            SelectableArea::Sorting => {
+                let selected_index = self
+                    .state
+                    .sort_list
+                    .selected()
+                    .expect("Failed to get selected sorting");
+                self.finder.remove_order(selected_index);
+                self.finder.sort();
+            }
+            SelectableArea::Presets => self.delete_preset(),
+            _ => {}
+        }
+    }
+
+    /// sorts by the selected order as a tie breaker for the current ones
+    fn handle_add_order(&mut self) {
+        if self.state.focus_pane == SelectableArea::Sorting {
             let selected_index = self
                 .state
-                .pred_list
+                .sort_list
                 .selected()
-                .expect("Failed to get selected predicate");
-            self.finder.remove_predicate(selected_index);
+                .expect("Failed to get selected sorting");
+            self.finder.add_order(selected_index);
+            self.finder.sort();
+        }
+    }
+
+    fn handle_negate(&mut self) {
+        if SelectableArea::Predicates == self.state.focus_pane {
+            if let Some(path) = self.selected_predicate_path() {
+                self.finder.negate_predicate(&path);
+            }
+        }
+    }
+
+    fn handle_reroll(&mut self) {
+        if self.state.focus_pane == SelectableArea::Sorting {
+            self.finder.reroll_seed();
+        }
+    }
+
+    fn handle_edit_seed(&mut self) {
+        if self.state.focus_pane == SelectableArea::Sorting {
+            if let Some(seed) = self.finder.seed() {
+                self.state.insert_buf = seed.to_string();
+                self.state.input_mode = InputMode::Insert;
+            }
+        }
+    }
+
+    fn update_seed(&mut self) {
+        if let Ok(seed) = self.state.insert_buf.parse() {
+            self.finder.set_seed(seed);
+        }
+    }
+
+    fn load_file(&mut self, path: &str) {
+        self.finder.start_load(path);
+        self.state.loading_path = path.to_string();
+        self.state.word_list.select(Some(0));
+    }
+
+    fn handle_loaded(&mut self, result: Result<LoadStats, LoadError>) {
+        // with nothing loaded yet, fall back to the default list, but keep the reason on screen
+        if self.state.falling_back {
+            self.state.falling_back = false;
+            if result.is_ok() {
+                return;
+            }
+        } else if result.is_err()
+            && self.finder.lists().is_empty()
+            && self.state.loading_path != DEFAULT_FILE_PATH
+        {
+            self.state.falling_back = true;
+            self.load_file(DEFAULT_FILE_PATH);
+        }
+
+        self.state.load_status = Some(result);
+    }
+
+    fn handle_escape(&mut self) {
+        if matches!(
+            self.state.focus_pane,
+            SelectableArea::Presets | SelectableArea::Lists
+        ) {
+            self.state.focus_pane = SelectableArea::Predicates;
         }
     }
 
+    // list browser ------------------------------------------------------------
+
+    fn open_lists(&mut self) {
+        self.state.list_scan = Some(Task::spawn(|progress| {
+            progress.send(lists::scan_lists(LISTS_DIR, 20));
+        }));
+        self.state.list_files.clear();
+        self.state.list_error = None;
+        self.state.focus_pane = SelectableArea::Lists;
+        self.state.list_browser.select(Some(0));
+    }
+
+    fn poll_list_scan(&mut self) {
+        let Some(result) = self.state.list_scan.as_ref().and_then(|t| t.try_recv()) else {
+            return;
+        };
+        self.state.list_scan = None;
+        match result {
+            Ok(files) => self.state.list_files = files,
+            Err(e) => self.state.list_error = Some(e),
+        }
+    }
+
+    fn selected_list_file(&self) -> Option<&ListFile> {
+        self.state
+            .list_browser
+            .selected()
+            .and_then(|i| self.state.list_files.get(i))
+    }
+
+    fn load_selected_list(&mut self) {
+        let Some(path) = self.selected_list_file().map(|f| f.path.clone()) else {
+            return;
+        };
+        self.load_file(&path);
+        self.state.focus_pane = SelectableArea::Words;
+    }
+
+    /// adds the selected list to the ones already loaded
+    fn combine_list(&mut self, op: lists::ListOp) {
+        if self.state.focus_pane != SelectableArea::Lists {
+            return;
+        }
+        let Some(path) = self.selected_list_file().map(|f| f.path.clone()) else {
+            return;
+        };
+        let spec = format!("{} {} {}", self.finder.file_path, op.symbol(), path);
+        self.load_file(&spec);
+        self.state.focus_pane = SelectableArea::Words;
+    }
+
+    // presets -----------------------------------------------------------------
+
+    fn open_presets(&mut self) {
+        match preset::load_presets(PRESETS_PATH) {
+            Ok(presets) => {
+                self.state.presets = presets;
+                self.state.preset_error = None;
+            }
+            Err(e) => self.state.preset_error = Some(e),
+        }
+        self.state.focus_pane = SelectableArea::Presets;
+        self.state.preset_list.select(Some(0));
+    }
+
+    fn handle_name_preset(&mut self) {
+        self.state.focus_pane = SelectableArea::PresetName;
+        self.state.insert_buf = String::new();
+        self.state.input_mode = InputMode::Insert;
+    }
+
+    /// saves the current setup under the typed name, then shows it in the picker
+    fn save_preset(&mut self) {
+        let name = self.state.insert_buf.trim();
+        if name.is_empty() {
+            self.state.focus_pane = SelectableArea::Predicates;
+            return;
+        }
+
+        let result = preset::save_preset(PRESETS_PATH, Preset::from_finder(name, &self.finder));
+        self.open_presets();
+        if let Err(e) = result {
+            self.state.preset_error = Some(e);
+        }
+    }
+
+    fn apply_preset(&mut self) {
+        let Some(p) = self
+            .state
+            .preset_list
+            .selected()
+            .and_then(|i| self.state.presets.get(i))
+            .cloned()
+        else {
+            return;
+        };
+
+        if let Err(e) = p.apply(&mut self.finder) {
+            self.state.preset_error = Some(e);
+            return;
+        }
+        self.load_file(&p.file_path);
+        self.state.pred_list.select(Some(0));
+        self.state.focus_pane = SelectableArea::Predicates;
+    }
+
+    fn delete_preset(&mut self) {
+        let Some(p) = self
+            .state
+            .preset_list
+            .selected()
+            .and_then(|i| self.state.presets.get(i))
+        else {
+            return;
+        };
+
+        let result = preset::delete_preset(PRESETS_PATH, &p.name);
+        self.open_presets();
+        if let Err(e) = result {
+            self.state.preset_error = Some(e);
+        }
+    }
+
+    // -------------------------------------------------------------------------
+
+    fn handle_edit_export(&mut self) {
+        self.state.focus_pane = SelectableArea::Export;
+        self.state.insert_buf.clone_from(&self.state.export_path);
+        self.state.input_mode = InputMode::Insert;
+    }
+
+    /// writes the found words, in their current order, to the typed path
+    fn export(&mut self) {
+        let path = std::mem::take(&mut self.state.insert_buf);
+        let rack = self.finder.rack();
+
+        self.state.export_status = Some(
+            export::export_file(&path, self.finder.iter_filtered(), rack)
+                .map(|count| format!("exported {} words to {}", count, path)),
+        );
+        self.state.export_path = path;
+        self.state.focus_pane = SelectableArea::Words;
+    }
+
+    fn handle_edit_query(&mut self) {
+        self.state.focus_pane = SelectableArea::Query;
+        self.state.insert_buf = self.finder.get_query();
+        self.state.query_before.clone_from(&self.state.insert_buf);
+        self.state.input_mode = InputMode::Insert;
+    }
+
+    /// applies the query as it's typed, the predicates stay as they were while it doesn't parse
+    fn update_query(&mut self) {
+        self.state.query_error = self.finder.set_query(&self.state.insert_buf).err();
+        self.state.word_list.select(Some(0));
+    }
+
     fn handle_edit_file(&mut self) {
         self.state.focus_pane = SelectableArea::File;
         self.state.insert_buf.clone_from(&self.finder.file_path);
@@ -250,14 +712,26 @@ impl App {
 
     /// i dont like this function
     fn update_predicate(&mut self) {
+        if let Some(path) = self.selected_predicate_path() {
+            self.finder.update_predicate(&path, &self.state.insert_buf);
+        }
+        self.state.word_list.select(Some(0));
+    }
+
+    /// path to the predicate or group selected in the predicates pane, if one is selected
+    fn selected_predicate_path(&self) -> Option<Vec<usize>> {
         let selected_index = self
             .state
             .pred_list
             .selected()
             .expect("Failed to get selected predicate");
-        self.finder
-            .update_predicate(selected_index, &self.state.insert_buf);
-        self.state.word_list.select(Some(0));
+        let rows = self.finder.predicate_rows();
+        let row = rows.get(selected_index)?;
+
+        match row.kind {
+            ExprRowKind::New => None,
+            _ => Some(row.path.clone()),
+        }
     }
 }
 
@@ -295,7 +769,63 @@ impl Widget for &mut App {
             path = self.state.insert_buf.clone().yellow().italic().reversed();
         }
 
-        let path_text = Line::from(vec![" Word List: ".into(), path]);
+        let mut path_text = Line::from(vec![" Word List: ".into(), path]);
+
+        match &self.state.load_status {
+            _ if self.finder.is_loading() => path_text.push_span(" (loading…)".dark_gray()),
+            Some(Ok(stats)) if self.finder.lists().len() > 1 => {
+                // how big each list was, so it's clear how much combining them took away
+                let mut lists = String::new();
+                for (i, list) in self.finder.lists().iter().enumerate() {
+                    if i > 0 {
+                        lists.push_str(&format!(" {} ", list.op.symbol()));
+                    }
+                    lists.push_str(&format!("{} {}", list.name(), list.words));
+                }
+                path_text.push_span(
+                    format!(
+                        " ({} words from {}, {})",
+                        stats.words,
+                        lists,
+                        stats.skipped()
+                    )
+                    .dark_gray(),
+                )
+            }
+            Some(Ok(stats)) => path_text
+                .push_span(format!(" ({} words, {})", stats.words, stats.skipped()).dark_gray()),
+            Some(Err(e)) => path_text.push_span(format!(" ({})", e).red()),
+            None => {}
+        }
+        if let Some(e) = &self.state.session_error {
+            path_text.push_span(format!(" ({})", e).red());
+        }
+
+        if self.state.focus_pane == SelectableArea::Export {
+            path_text = Line::from(vec![
+                " Export to: ".into(),
+                self.state.insert_buf.clone().yellow().italic().reversed(),
+                " (.txt, .csv or .json)".dark_gray(),
+            ]);
+        }
+
+        if self.state.focus_pane == SelectableArea::PresetName {
+            path_text = Line::from(vec![
+                " Save preset as: ".into(),
+                self.state.insert_buf.clone().yellow().italic().reversed(),
+            ]);
+        }
+
+        // the query being edited takes the place of the word list
+        if self.state.focus_pane == SelectableArea::Query {
+            path_text = Line::from(vec![
+                " Query: ".into(),
+                self.state.insert_buf.clone().yellow().italic().reversed(),
+            ]);
+            if let Some(e) = &self.state.query_error {
+                path_text.push_span(format!(" ({})", e).red());
+            }
+        }
 
         Paragraph::new(path_text)
             .block(Block::new().borders(Borders::ALL))
@@ -320,7 +850,9 @@ impl Widget for &mut App {
         // footer - controls
 
         let mut footer_text = match self.state.input_mode {
-            InputMode::Normal => "q: quit | ←/→: switch panes | ↑/↓: select | f: edit file path",
+            InputMode::Normal => {
+                "q: quit | ←/→: switch panes | ↑/↓: select | f: edit file path | l: lists | /: edit query | p: presets | w: save preset | e: export"
+            }
             InputMode::Insert => " ←: backspace | ↵: save",
         }
         .to_string();
@@ -329,11 +861,33 @@ impl Widget for &mut App {
             SelectableArea::File => {
                 footer_text.push_str(" | ↵: save file path");
             }
+            SelectableArea::Presets => {
+                footer_text.push_str(" | ↵: load preset | del: delete preset | esc: close");
+            }
+            SelectableArea::PresetName => {
+                footer_text.push_str(" | ↵: save preset");
+            }
+            SelectableArea::Lists => {
+                footer_text.push_str(
+                    " | ↵: load list | +/&/-: union/intersect/subtract with current | esc: close",
+                );
+            }
+            SelectableArea::Export => {
+                footer_text.push_str(" | ↵: export found words");
+            }
+            SelectableArea::Query => {
+                footer_text.push_str(" | esc: put back the old query");
+            }
             SelectableArea::Sorting => {
-                footer_text.push_str(" | ↵: set sorting");
+                footer_text.push_str(" | ↵: set sorting | t: then sort by | del: remove sorting");
+                if self.finder.seed().is_some() {
+                    footer_text.push_str(" | r: reroll seed | s: edit seed");
+                }
             }
             SelectableArea::Predicates => {
-                footer_text.push_str(" | ↵: edit predicate | del: remove predicate");
+                footer_text.push_str(
+                    " | ↵: edit predicate / switch group | n: negate | del: remove predicate",
+                );
             }
             _ => {}
         }
@@ -347,6 +901,14 @@ impl Widget for &mut App {
         if self.state.focus_pane == SelectableArea::NewPredicate {
             self.render_new_predicate_pane(popup_area, buf);
         }
+
+        if self.state.focus_pane == SelectableArea::Presets {
+            self.render_presets_pane(popup_area, buf);
+        }
+
+        if self.state.focus_pane == SelectableArea::Lists {
+            self.render_lists_pane(popup_area, buf);
+        }
     }
 }
 
@@ -358,30 +920,87 @@ impl Widget for &mut App {
 
 impl App {
     fn render_words_pane(&mut self, area: Rect, buf: &mut Buffer) {
-        let items: Vec<Line> = self
-            .finder
-            .iter_filtered()
-            .map(|w| w.to_line().magenta())
+        // only the words that fit are turned into lines, the full list can be hundreds of
+        // thousands long. `word_list` keeps the selection and offset into the whole list
+        let height = area.height.saturating_sub(2).max(1) as usize;
+        let total = self.finder.found().len();
+        let selected = self
+            .state
+            .word_list
+            .selected()
+            .unwrap_or(0)
+            .min(total.saturating_sub(1));
+
+        // keep the selection on screen without scrolling past the end
+        let offset = self
+            .state
+            .word_list
+            .offset()
+            .min(selected)
+            .max((selected + 1).saturating_sub(height))
+            .min(total.saturating_sub(height));
+
+        let window = self.finder.found().skip(offset).take(height);
+
+        // with several lists, show which ones each word is in
+        let show_sources = self.finder.lists().len() > 1;
+
+        let items: Vec<Line> = window
+            .map(|w| {
+                let mut line = w.to_line().magenta();
+                if let Some(f) = self.finder.frequency(w) {
+                    line.push_span(format!(" {}", f).dark_gray());
+                }
+                if show_sources {
+                    let from = format!(" ({})", self.finder.provenance(w));
+                    line.push_span(from.dark_gray());
+                }
+                line
+            })
             .collect();
 
-        let block = Block::bordered()
-            .title("Found Words")
+        let title = match self.finder.filter_progress() {
+            Some((checked, of)) => format!(
+                "Found Words (filtering… {} so far, {}%)",
+                total,
+                checked * 100 / of.max(1)
+            ),
+            None => format!("Found Words ({})", total),
+        };
+
+        let mut block = Block::bordered()
+            .title(title)
             .title_alignment(Alignment::Center);
 
+        match &self.state.export_status {
+            Some(Ok(message)) => block = block.title_bottom(message.clone().green()),
+            Some(Err(e)) => block = block.title_bottom(e.clone().red()),
+            None => {}
+        }
+
         let mut list = List::new(items).block(block);
 
         if self.state.focus_pane == SelectableArea::Words {
             list = list.highlight_style(Style::new().add_modifier(Modifier::REVERSED));
         }
 
-        StatefulWidget::render(list, area, buf, &mut self.state.word_list);
+        self.state.word_list.select(Some(selected));
+        *self.state.word_list.offset_mut() = offset;
+
+        let mut window_state = ListState::default().with_selected(Some(selected - offset));
+        StatefulWidget::render(list, area, buf, &mut window_state);
     }
 
     fn render_sorting_pane(&mut self, area: Rect, buf: &mut Buffer) {
+        // number the orders in use by priority
         let items: Vec<String> = self
             .finder
             .iter_order_names()
-            .map(|s| s.to_string())
+            .enumerate()
+            .map(|(i, s)| match self.finder.order_position(i) {
+                Some(p) => format!("{}. {}", p + 1, s),
+                None => format!("   {}", s),
+            })
             .collect();
 
         // let words: Vec<Line> = self
@@ -390,8 +1009,25 @@ impl App {
         //     .map(|w| w.to_line().magenta())
         //     .collect();
 
+        let editing_seed = self.state.focus_pane == SelectableArea::Sorting
+            && self.state.input_mode == InputMode::Insert;
+
+        let mut order = Vec::new();
+        for (i, o) in self.finder.word_orders.iter().enumerate() {
+            if i > 0 {
+                order.push(", then ".into());
+            }
+            match o {
+                WordOrder::Random(_) if editing_seed => {
+                    order.push(format!("Random (seed {})", self.state.insert_buf).italic())
+                }
+                o => order.push(o.to_string().into()),
+            }
+        }
+
         let block = Block::bordered()
             .title("Sorting")
+            .title_bottom(Line::from(order))
             .title_alignment(Alignment::Center);
 
         let mut list = List::new(items).block(block);
@@ -404,13 +1040,33 @@ impl App {
     }
 
     fn render_predicate_pane(&mut self, area: Rect, buf: &mut Buffer) {
-        let mut items: Vec<String> = self
+        let items: Vec<Line> = self
             .finder
-            .predicates
+            .predicate_rows()
             .iter()
-            .map(|f| f.to_string())
+            .map(|row| {
+                let mut spans: Vec<Span> = vec!["  ".repeat(row.depth).into()];
+                if row.negated {
+                    spans.push("Not ".red());
+                }
+                match row.kind {
+                    ExprRowKind::Pred(p) => {
+                        spans.push(p.to_string().into());
+                        let error = match (p.get_error(), p.get_error_pos()) {
+                            (Some(e), Some(pos)) => Some(format!("{} at column {}", e, pos + 1)),
+                            (e, _) => e.map(|e| e.to_string()),
+                        };
+                        if let Some(e) = error {
+                            spans.push(format!(" ({})", e).red());
+                        }
+                    }
+                    ExprRowKind::AnyOf => spans.push("Any of:".cyan()),
+                    ExprRowKind::AllOf => spans.push("All of:".cyan()),
+                    ExprRowKind::New => spans.push("+ New Predicate".into()),
+                }
+                Line::from(spans)
+            })
             .collect();
-        items.push("+ New Predicate".to_string());
 
         let block = Block::bordered()
             .title("Predicates")
@@ -451,6 +1107,95 @@ impl App {
         Clear.render(area, buf);
         StatefulWidget::render(list, area, buf, &mut self.state.new_pred_list);
     }
+
+    fn render_presets_pane(&mut self, area: Rect, buf: &mut Buffer) {
+        let items: Vec<Line> = self
+            .state
+            .presets
+            .iter()
+            .map(|p| {
+                Line::from(vec![
+                    p.name.clone().blue(),
+                    format!("  {}", p.query).dark_gray(),
+                ])
+            })
+            .collect();
+
+        let mut block = Block::default()
+            .title("Presets")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL);
+
+        if let Some(e) = &self.state.preset_error {
+            block = block.title_bottom(e.clone().red());
+        } else if self.state.presets.is_empty() {
+            block = block.title_bottom("no presets yet, w saves the current search".dark_gray());
+        }
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+        // clear the area first so the popup appears on top
+        Clear.render(area, buf);
+        StatefulWidget::render(list, area, buf, &mut self.state.preset_list);
+    }
+
+    fn render_lists_pane(&mut self, area: Rect, buf: &mut Buffer) {
+        let [files_area, preview_area] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(70), Constraint::Percentage(30)])
+            .areas(area);
+
+        let items: Vec<Line> = self
+            .state
+            .list_files
+            .iter()
+            .map(|f| {
+                let mut line = Line::from(vec![
+                    f.name().to_string().blue(),
+                    format!("  {}", lists::format_size(f.size)).dark_gray(),
+                ]);
+                match &f.words {
+                    Ok((count, _)) => line.push_span(format!(", {} words", count).dark_gray()),
+                    Err(_) => line.push_span(", can't be loaded".red()),
+                }
+                line
+            })
+            .collect();
+
+        let mut block = Block::bordered()
+            .title(format!("Word Lists in {}", LISTS_DIR))
+            .title_alignment(Alignment::Center);
+
+        if let Some(e) = &self.state.list_error {
+            block = block.title_bottom(e.clone().red());
+        } else if self.state.list_scan.is_some() {
+            block = block.title_bottom("reading lists…".dark_gray());
+        } else if self.state.list_files.is_empty() {
+            block = block.title_bottom("no word lists found".dark_gray());
+        }
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+        // the first few words of the selected list, or why it can't be loaded
+        let preview: Vec<Line> = match self.selected_list_file().map(|f| &f.words) {
+            Some(Ok((_, words))) => words.iter().map(|w| w.to_line().magenta()).collect(),
+            Some(Err(e)) => vec![e.clone().red().into()],
+            None => Vec::new(),
+        };
+
+        let preview = Paragraph::new(preview)
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().title("Preview"));
+
+        // clear the area first so the popup appears on top
+        Clear.render(area, buf);
+        preview.render(preview_area, buf);
+        StatefulWidget::render(list, files_area, buf, &mut self.state.list_browser);
+    }
 }
 
 // =============================================================================
@@ -0,0 +1,185 @@
+use crate::{
+    export::{self, ExportFormat},
+    ord::ORDER_NAMES,
+    pred::PREDICATE_NAMES,
+    query,
+    words::{WordFinder, DEFAULT_FILE_PATH},
+};
+use std::{
+    io::{self, BufWriter},
+    process::ExitCode,
+};
+
+const USAGE: &str = "\
+usage: words [options]
+
+runs a single search and prints the matching words, one per line. without any options the
+interactive interface is started instead
+
+options:
+  -l, --list PATH             word list to search (default ./lists/words_alpha.txt), several
+                              can be combined like \"a.txt & b.txt - c.txt\" with + for
+                              union, & for intersection and - for difference
+  -p, --predicate NAME=VALUE  only keep words matching the predicate, can be repeated
+  -q, --query QUERY           only keep words matching the query, e.g. \"len:5 !contains:e\"
+  -s, --sort NAME             sort the words, repeat to break ties with further orders
+      --seed N                seed for the random order
+  -f, --format FORMAT         print as text (default), csv or json
+  -h, --help                  show this message
+
+predicate names are case insensitive:
+";
+
+/// A search given on the command line
+#[derive(Debug)]
+pub struct Query {
+    list: String,
+    /// index into `PREDICATE_NAMES` and the value to update it with
+    predicates: Vec<(usize, String)>,
+    queries: Vec<String>,
+    /// indices into `ORDER_NAMES`
+    orders: Vec<usize>,
+    seed: Option<u64>,
+    format: ExportFormat,
+}
+
+/// `Ok(None)` when help was asked for
+pub fn parse_args(args: &[String]) -> Result<Option<Query>, String> {
+    let mut query = Query {
+        list: DEFAULT_FILE_PATH.to_string(),
+        predicates: Vec::new(),
+        queries: Vec::new(),
+        orders: Vec::new(),
+        seed: None,
+        format: ExportFormat::Text,
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-l" | "--list" => query.list = value()?.to_string(),
+            "-p" | "--predicate" => {
+                let value = value()?;
+                let (name, s) = value
+                    .split_once('=')
+                    .ok_or_else(|| format!("expected NAME=VALUE, got \"{}\"", value))?;
+                let index = find_name(&PREDICATE_NAMES, name)
+                    .ok_or_else(|| format!("unknown predicate \"{}\"", name))?;
+                query.predicates.push((index, s.to_string()));
+            }
+            "-q" | "--query" => query.queries.push(value()?.to_string()),
+            "-s" | "--sort" => {
+                let name = value()?;
+                let index = find_name(&ORDER_NAMES, name)
+                    .ok_or_else(|| format!("unknown sorting \"{}\"", name))?;
+                query.orders.push(index);
+            }
+            "--seed" => {
+                let seed = value()?;
+                query.seed = Some(
+                    seed.parse()
+                        .map_err(|_| format!("\"{}\" is not a seed", seed))?,
+                );
+            }
+            "-f" | "--format" => {
+                let name = value()?;
+                query.format = ExportFormat::from_name(name)
+                    .ok_or_else(|| format!("unknown format \"{}\"", name))?;
+            }
+            arg => return Err(format!("unknown argument \"{}\"", arg)),
+        }
+    }
+
+    Ok(Some(query))
+}
+
+fn find_name(names: &[&str], name: &str) -> Option<usize> {
+    names
+        .iter()
+        .position(|n| n.eq_ignore_ascii_case(name.trim()))
+}
+
+fn print_usage() {
+    eprint!("{}", USAGE);
+    for name in PREDICATE_NAMES {
+        eprintln!("  {}", name);
+    }
+    eprintln!("\nsorting names are case insensitive:");
+    for name in ORDER_NAMES {
+        eprintln!("  {}", name);
+    }
+}
+
+/// runs the search from the command line, exiting with 1 if nothing matched and 2 if the search
+/// couldn't be run at all
+pub fn run(args: &[String]) -> ExitCode {
+    let query = match parse_args(args) {
+        Ok(Some(query)) => query,
+        Ok(None) => {
+            print_usage();
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            eprintln!("try --help");
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut finder = WordFinder::default();
+    finder.predicates.clear();
+
+    for (i, (index, s)) in query.predicates.iter().enumerate() {
+        finder.add_predicate(&[], *index);
+        finder.update_predicate(&[i], s);
+    }
+    for p in finder.predicates.iter().filter_map(|e| e.predicate()) {
+        if let Some(e) = p.get_error() {
+            match p.get_error_pos() {
+                Some(pos) => eprintln!("error: {}: {} at column {}", p, e, pos + 1),
+                None => eprintln!("error: {}: {}", p, e),
+            }
+            return ExitCode::from(2);
+        }
+    }
+    for s in &query.queries {
+        match query::parse(s) {
+            Ok(predicates) => finder.predicates.extend(predicates),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                eprintln!("  {}", s);
+                eprintln!("  {}^", " ".repeat(e.pos));
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    if !query.orders.is_empty() {
+        finder.set_orders(&query.orders, query.seed);
+    } else if let Some(seed) = query.seed {
+        finder.set_seed(seed);
+    }
+
+    // loading sorts with the orders that are set
+    if let Err(e) = finder.load_file(&query.list) {
+        eprintln!("error: {}", e);
+        return ExitCode::from(2);
+    }
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    let words = finder.iter_filtered();
+
+    match export::write_words(&mut out, query.format, words, finder.rack()) {
+        Ok(0) => ExitCode::from(1),
+        Ok(_) => ExitCode::SUCCESS,
+        // most likely the output was piped into something like `head` that stopped reading
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::scrabble;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    /// one word per line
+    Text,
+    /// `word,length,score` with a header row
+    Csv,
+    /// an array of `{"word": ..., "length": ..., "score": ...}` objects
+    Json,
+}
+
+impl ExportFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "text" | "txt" => Some(ExportFormat::Text),
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// picks the format from the file extension, plain text if it isn't csv or json
+    pub fn from_path(path: &str) -> Self {
+        std::path::Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(ExportFormat::from_name)
+            .unwrap_or(ExportFormat::Text)
+    }
+}
+
+/// writes the words in the given format, returning how many there were
+///
+/// scores are the same as for sorting, so letters missing from a known `rack` are worth nothing
+pub fn write_words<'a>(
+    out: &mut impl Write,
+    format: ExportFormat,
+    words: impl Iterator<Item = &'a String>,
+    rack: Option<&str>,
+) -> io::Result<usize> {
+    let mut count = 0;
+
+    match format {
+        ExportFormat::Text => {
+            for word in words {
+                writeln!(out, "{}", word)?;
+                count += 1;
+            }
+        }
+        ExportFormat::Csv => {
+            // words are only ever letters, so nothing needs quoting
+            writeln!(out, "word,length,score")?;
+            for word in words {
+                let (len, score) = (word.chars().count(), scrabble::score(word, rack));
+                writeln!(out, "{},{},{}", word, len, score)?;
+                count += 1;
+            }
+        }
+        ExportFormat::Json => {
+            write!(out, "[")?;
+            for word in words {
+                if count > 0 {
+                    write!(out, ",")?;
+                }
+                let (len, score) = (word.chars().count(), scrabble::score(word, rack));
+                write!(
+                    out,
+                    "\n  {{\"word\": \"{}\", \"length\": {}, \"score\": {}}}",
+                    json_escape(word),
+                    len,
+                    score
+                )?;
+                count += 1;
+            }
+            writeln!(out, "\n]")?;
+        }
+    }
+
+    out.flush()?;
+    Ok(count)
+}
+
+/// words are only ever letters, but they can be any unicode letters, which json takes as they are
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// writes the words to a file, in the format its extension asks for
+pub fn export_file<'a>(
+    path: &str,
+    words: impl Iterator<Item = &'a String>,
+    rack: Option<&str>,
+) -> Result<usize, String> {
+    let error = |e: io::Error| format!("couldn't write {}: {}", path, e);
+
+    let mut out = BufWriter::new(File::create(path).map_err(error)?);
+    write_words(&mut out, ExportFormat::from_path(path), words, rack).map_err(error)
+}
@@ -0,0 +1,301 @@
+use crate::{
+    lists::WordInfo,
+    pred::{WordPredicate, PREDICATE_NAMES},
+};
+
+/// A boolean combination of word predicates
+///
+/// the finder keeps a list of these that all have to match, so a plain list of `Pred`s behaves
+/// exactly like the old flat list of predicates
+#[derive(Debug, Clone)]
+pub enum PredicateExpr {
+    Pred(WordPredicate),
+    Not(Box<PredicateExpr>),
+    AnyOf(Vec<PredicateExpr>),
+    AllOf(Vec<PredicateExpr>),
+}
+
+pub const GROUP_NAMES: [&str; 2] = ["Any of (group)", "All of (group)"];
+
+impl PredicateExpr {
+    /// indices past the end of `PREDICATE_NAMES` continue into `GROUP_NAMES`
+    pub fn from_index(index: usize) -> Option<Self> {
+        if let Some(p) = WordPredicate::from_index(index) {
+            return Some(PredicateExpr::Pred(p));
+        }
+
+        match index - PREDICATE_NAMES.len() {
+            0 => Some(PredicateExpr::AnyOf(Vec::new())),
+            1 => Some(PredicateExpr::AllOf(Vec::new())),
+            _ => None,
+        }
+    }
+
+    /// an empty group doesn't constrain anything, so a group that was just added doesn't hide
+    /// every word before anything is put in it
+    pub fn matches(&self, word: &str, info: WordInfo) -> bool {
+        match self {
+            PredicateExpr::Pred(p) => p.matches(word, info),
+            PredicateExpr::Not(e) => !e.matches(word, info),
+            PredicateExpr::AnyOf(es) => es.is_empty() || es.iter().any(|e| e.matches(word, info)),
+            PredicateExpr::AllOf(es) => es.iter().all(|e| e.matches(word, info)),
+        }
+    }
+
+    /// toggles negation, unwrapping instead of stacking `Not`s
+    pub fn negate(&mut self) {
+        let e = std::mem::replace(self, PredicateExpr::AllOf(Vec::new()));
+        *self = match e {
+            PredicateExpr::Not(inner) => *inner,
+            e => PredicateExpr::Not(Box::new(e)),
+        };
+    }
+
+    /// the expression under any number of `Not`s
+    fn peel(&self) -> &PredicateExpr {
+        match self {
+            PredicateExpr::Not(e) => e.peel(),
+            e => e,
+        }
+    }
+
+    fn peel_mut(&mut self) -> &mut PredicateExpr {
+        match self {
+            PredicateExpr::Not(e) => e.peel_mut(),
+            e => e,
+        }
+    }
+
+    pub fn predicate(&self) -> Option<&WordPredicate> {
+        match self.peel() {
+            PredicateExpr::Pred(p) => Some(p),
+            _ => None,
+        }
+    }
+
+    pub fn predicate_mut(&mut self) -> Option<&mut WordPredicate> {
+        match self.peel_mut() {
+            PredicateExpr::Pred(p) => Some(p),
+            _ => None,
+        }
+    }
+
+    fn children(&self) -> Option<&Vec<PredicateExpr>> {
+        match self.peel() {
+            PredicateExpr::AnyOf(es) | PredicateExpr::AllOf(es) => Some(es),
+            _ => None,
+        }
+    }
+
+    fn children_mut(&mut self) -> Option<&mut Vec<PredicateExpr>> {
+        match self.peel_mut() {
+            PredicateExpr::AnyOf(es) | PredicateExpr::AllOf(es) => Some(es),
+            _ => None,
+        }
+    }
+
+    /// the most checking a word can take, see `WordPredicate::cost`
+    fn cost(&self) -> f64 {
+        match self {
+            PredicateExpr::Pred(p) => p.cost(),
+            PredicateExpr::Not(e) => e.cost(),
+            PredicateExpr::AnyOf(es) | PredicateExpr::AllOf(es) => {
+                es.iter().map(|e| e.cost()).sum()
+            }
+        }
+    }
+
+    /// switches a group between any of and all of, keeping its children
+    pub fn toggle_group(&mut self) {
+        let e = self.peel_mut();
+        *e = match std::mem::replace(e, PredicateExpr::AllOf(Vec::new())) {
+            PredicateExpr::AnyOf(es) => PredicateExpr::AllOf(es),
+            PredicateExpr::AllOf(es) => PredicateExpr::AnyOf(es),
+            e => e,
+        };
+    }
+}
+
+// paths -----------------------------------------------------------------------
+// a node in the tree is addressed by the list of child indices leading to it, starting in the
+// top level list. the empty path stands for the top level list itself wherever a group is expected
+
+pub fn get<'a>(list: &'a [PredicateExpr], path: &[usize]) -> Option<&'a PredicateExpr> {
+    let (first, rest) = path.split_first()?;
+    let mut e = list.get(*first)?;
+    for i in rest {
+        e = e.children()?.get(*i)?;
+    }
+    Some(e)
+}
+
+pub fn get_mut<'a>(list: &'a mut [PredicateExpr], path: &[usize]) -> Option<&'a mut PredicateExpr> {
+    let (first, rest) = path.split_first()?;
+    let mut e = list.get_mut(*first)?;
+    for i in rest {
+        e = e.children_mut()?.get_mut(*i)?;
+    }
+    Some(e)
+}
+
+/// whether the node at `path` is under an odd number of `Not`s, counting its own, so that making
+/// it match fewer words makes the whole tree match more
+pub fn is_negated(list: &[PredicateExpr], path: &[usize]) -> bool {
+    let mut negated = false;
+    let mut list = list;
+    for i in path {
+        let Some(mut e) = list.get(*i) else {
+            return false;
+        };
+        while let PredicateExpr::Not(inner) = e {
+            negated = !negated;
+            e = inner;
+        }
+        list = e.children().map(|es| es.as_slice()).unwrap_or_default();
+    }
+    negated
+}
+
+/// the predicates every matching word has to satisfy on its own, the ones not under a `Not` or
+/// an "any of" group
+pub fn required<'a>(list: &'a [PredicateExpr], found: &mut Vec<&'a WordPredicate>) {
+    for e in list {
+        match e {
+            PredicateExpr::Pred(p) => found.push(p),
+            PredicateExpr::AllOf(es) => required(es, found),
+            PredicateExpr::Not(_) | PredicateExpr::AnyOf(_) => {}
+        }
+    }
+}
+
+// planning --------------------------------------------------------------------
+
+/// a copy of the list reordered to be quick to check, given the share of words each expression
+/// matches. the result is the same in any order, but an "all of" list can stop at the first
+/// expression that fails and an "any of" group at the first that matches, so cheap expressions
+/// that settle it for most words go first. the finder keeps showing them in the order they were
+/// added
+pub fn plan(
+    list: &[PredicateExpr],
+    pass_rate: &impl Fn(&PredicateExpr) -> f64,
+) -> Vec<PredicateExpr> {
+    // the expected cost of checking everything is lowest when sorted by cost over how often each
+    // one ends the check
+    plan_list(list, pass_rate, |rate| 1.0 - rate)
+}
+
+fn plan_list(
+    list: &[PredicateExpr],
+    pass_rate: &impl Fn(&PredicateExpr) -> f64,
+    settles: impl Fn(f64) -> f64,
+) -> Vec<PredicateExpr> {
+    let mut planned: Vec<(f64, PredicateExpr)> = list
+        .iter()
+        .map(|e| {
+            let e = plan_expr(e, pass_rate);
+            let rank = e.cost() / settles(pass_rate(&e)).max(0.001);
+            (rank, e)
+        })
+        .collect();
+    planned.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+    planned.into_iter().map(|(_, e)| e).collect()
+}
+
+fn plan_expr(e: &PredicateExpr, pass_rate: &impl Fn(&PredicateExpr) -> f64) -> PredicateExpr {
+    match e {
+        PredicateExpr::Pred(p) => PredicateExpr::Pred(p.clone()),
+        PredicateExpr::Not(e) => PredicateExpr::Not(Box::new(plan_expr(e, pass_rate))),
+        PredicateExpr::AllOf(es) => PredicateExpr::AllOf(plan(es, pass_rate)),
+        PredicateExpr::AnyOf(es) => PredicateExpr::AnyOf(plan_list(es, pass_rate, |rate| rate)),
+    }
+}
+
+/// the list of children of the group at `path`
+pub fn group_mut<'a>(
+    list: &'a mut Vec<PredicateExpr>,
+    path: &[usize],
+) -> Option<&'a mut Vec<PredicateExpr>> {
+    if path.is_empty() {
+        return Some(list);
+    }
+    get_mut(list, path)?.children_mut()
+}
+
+/// removes the node at `path` from its parent, along with everything under it
+pub fn remove(list: &mut Vec<PredicateExpr>, path: &[usize]) -> Option<PredicateExpr> {
+    let (last, parent) = path.split_last()?;
+    let siblings = group_mut(list, parent)?;
+    if *last < siblings.len() {
+        Some(siblings.remove(*last))
+    } else {
+        None
+    }
+}
+
+// flattening ------------------------------------------------------------------
+
+/// One line of the tree as shown in the predicates pane
+#[derive(Debug)]
+pub struct ExprRow<'a> {
+    pub path: Vec<usize>,
+    pub depth: usize,
+    pub negated: bool,
+    pub kind: ExprRowKind<'a>,
+}
+
+#[derive(Debug)]
+pub enum ExprRowKind<'a> {
+    Pred(&'a WordPredicate),
+    AnyOf,
+    AllOf,
+    /// placeholder for adding a new predicate to the group at `path`
+    New,
+}
+
+/// lists the tree depth first, with a `New` row closing every group
+pub fn rows(list: &[PredicateExpr]) -> Vec<ExprRow<'_>> {
+    let mut rows = Vec::new();
+    push_rows(list, &mut Vec::new(), &mut rows);
+    rows
+}
+
+fn push_rows<'a>(list: &'a [PredicateExpr], path: &mut Vec<usize>, rows: &mut Vec<ExprRow<'a>>) {
+    let depth = path.len();
+
+    for (i, e) in list.iter().enumerate() {
+        path.push(i);
+
+        let mut negated = false;
+        let mut inner = e;
+        while let PredicateExpr::Not(e) = inner {
+            negated = !negated;
+            inner = e;
+        }
+
+        let kind = match inner {
+            PredicateExpr::Pred(p) => ExprRowKind::Pred(p),
+            PredicateExpr::AnyOf(_) => ExprRowKind::AnyOf,
+            PredicateExpr::AllOf(_) => ExprRowKind::AllOf,
+            PredicateExpr::Not(_) => unreachable!(),
+        };
+        rows.push(ExprRow {
+            path: path.clone(),
+            depth,
+            negated,
+            kind,
+        });
+
+        if let Some(children) = inner.children() {
+            push_rows(children, path, rows);
+        }
+
+        path.pop();
+    }
+
+    rows.push(ExprRow {
+        path: path.clone(),
+        depth,
+        negated: false,
+        kind: ExprRowKind::New,
+    });
+}
@@ -0,0 +1,312 @@
+//! Several word lists combined into one, written as paths joined by operators applied left to right:
+//!
+//! ```text
+//! ./lists/dictionary.txt & ./lists/valid-wordle-words.txt - ./lists/answerlist.txt
+//! ```
+//!
+//! - `+` keeps the words in either list (union)
+//! - `&` keeps the words in both (intersection)
+//! - `-` drops the words in the list after it (difference)
+//!
+//! the operators need spaces around them so they can still be used inside paths
+
+use crate::{prime::Signature, words};
+use std::{cmp::Ordering, collections::HashMap};
+
+/// where word lists are kept
+pub const LISTS_DIR: &str = "./lists";
+
+/// the most lists that can be combined, one bit of a `u64` each
+pub const MAX_LISTS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ListOp {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl ListOp {
+    pub fn symbol(&self) -> char {
+        match self {
+            ListOp::Union => '+',
+            ListOp::Intersection => '&',
+            ListOp::Difference => '-',
+        }
+    }
+
+    fn from_symbol(s: &str) -> Option<Self> {
+        match s {
+            "+" => Some(ListOp::Union),
+            "&" => Some(ListOp::Intersection),
+            "-" => Some(ListOp::Difference),
+            _ => None,
+        }
+    }
+}
+
+/// One of the files that went into the word list
+#[derive(Debug, Clone)]
+pub struct ListSource {
+    pub path: String,
+    /// how it is combined with the lists before it, the first is always a union
+    pub op: ListOp,
+    pub words: usize,
+}
+
+impl ListSource {
+    /// the file name without its folder or extension, which is what `from:` predicates match
+    pub fn name(&self) -> &str {
+        list_name(&self.path)
+    }
+}
+
+pub fn list_name(path: &str) -> &str {
+    std::path::Path::new(path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(path)
+}
+
+/// splits a list spec into its paths and the operators in front of them
+pub fn parse_spec(spec: &str) -> Result<Vec<(ListOp, String)>, String> {
+    let mut lists = Vec::new();
+    let mut op = ListOp::Union;
+    let mut path: Vec<&str> = Vec::new();
+
+    // split on single spaces so paths with spaces in them survive
+    for part in spec.split(' ') {
+        match ListOp::from_symbol(part) {
+            Some(next) => {
+                if path.is_empty() {
+                    return Err(format!("expected a path before {}", part));
+                }
+                lists.push((op, path.join(" ").trim().to_string()));
+                path.clear();
+                op = next;
+            }
+            None => path.push(part),
+        }
+    }
+
+    let last = path.join(" ").trim().to_string();
+    if last.is_empty() {
+        if lists.is_empty() {
+            return Err("no word list given".to_string());
+        }
+        return Err(format!("expected a path after {}", op.symbol()));
+    }
+    lists.push((op, last));
+
+    if lists.len() > MAX_LISTS {
+        return Err(format!("at most {} lists can be combined", MAX_LISTS));
+    }
+
+    Ok(lists)
+}
+
+/// the spec that parses back to these lists
+pub fn write_spec(lists: &[ListSource]) -> String {
+    let mut spec = String::new();
+    for (i, list) in lists.iter().enumerate() {
+        if i > 0 {
+            spec.push_str(&format!(" {} ", list.op.symbol()));
+        }
+        spec.push_str(&list.path);
+    }
+    spec
+}
+
+/// combines the words of each list with its operator, returning the words that are left in the
+/// order they first appear and, for each of them, a bit set for every list it appears in
+pub fn combine(lists: &[(ListOp, Vec<String>)]) -> (Vec<String>, HashMap<String, u64>) {
+    let mut words = Vec::new();
+    let mut sources: HashMap<String, u64> = HashMap::new();
+    for (i, (_, list)) in lists.iter().enumerate() {
+        for word in list {
+            let mask = sources.entry(word.clone()).or_insert_with(|| {
+                words.push(word.clone());
+                0
+            });
+            *mask |= 1 << i;
+        }
+    }
+
+    let kept = |mask: u64| {
+        let mut kept = false;
+        for (i, (op, _)) in lists.iter().enumerate() {
+            let here = mask & (1 << i) != 0;
+            kept = match op {
+                ListOp::Union => kept || here,
+                ListOp::Intersection => kept && here,
+                ListOp::Difference => kept && !here,
+            };
+        }
+        kept
+    };
+
+    sources.retain(|_, mask| kept(*mask));
+    words.retain(|word| sources.contains_key(word));
+
+    (words, sources)
+}
+
+/// Which of the loaded lists a word is in
+#[derive(Debug, Clone, Copy)]
+pub struct Provenance<'a> {
+    pub lists: &'a [ListSource],
+    pub mask: u64,
+}
+
+impl<'a> Provenance<'a> {
+    pub fn iter(self) -> impl Iterator<Item = &'a ListSource> {
+        self.lists
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| self.mask & (1 << i) != 0)
+            .map(|(_, list)| list)
+    }
+
+    /// whether one of the lists is called `name` or is at the path `name`
+    pub fn contains(&self, name: &str) -> bool {
+        self.iter()
+            .any(|list| list.name().eq_ignore_ascii_case(name) || list.path == name)
+    }
+}
+
+/// What the loaded lists say about a word besides its spelling
+#[derive(Debug, Clone, Copy)]
+pub struct WordInfo<'a> {
+    pub from: Provenance<'a>,
+    /// how often the word is used, if its list has a frequency column
+    pub frequency: Option<Frequency>,
+    /// its letter counts, unless it has letters other than a-z
+    pub letters: Option<&'a Signature>,
+}
+
+/// How common a word is, from the second column of its list
+///
+/// the more common word is the greater one. counts and ranks can't be compared with each other,
+/// so any count is greater than any rank
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    /// how many times it's used
+    Count(u64),
+    /// its place in a list going from the most common word, which is 1
+    Rank(u64),
+}
+
+impl Frequency {
+    /// whether it's used at least `min` times, or is among the `min` most common for a rank
+    pub fn at_least(&self, min: u64) -> bool {
+        match self {
+            Frequency::Count(count) => *count >= min,
+            Frequency::Rank(rank) => *rank <= min,
+        }
+    }
+}
+
+impl Ord for Frequency {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Frequency::Count(a), Frequency::Count(b)) => a.cmp(b),
+            (Frequency::Rank(a), Frequency::Rank(b)) => a.cmp(b).reverse(),
+            (Frequency::Count(_), Frequency::Rank(_)) => Ordering::Greater,
+            (Frequency::Rank(_), Frequency::Count(_)) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for Frequency {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Frequency {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Frequency::Count(count) => write!(f, "{}", count),
+            Frequency::Rank(rank) => write!(f, "#{}", rank),
+        }
+    }
+}
+
+impl std::fmt::Display for Provenance<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, list) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", list.name())?;
+        }
+        Ok(())
+    }
+}
+
+// browsing --------------------------------------------------------------------
+
+/// A word list file found in `LISTS_DIR`
+#[derive(Debug)]
+pub struct ListFile {
+    pub path: String,
+    /// in bytes
+    pub size: u64,
+    /// how many words it has and the first few of them, or why it can't be loaded
+    pub words: Result<(usize, Vec<String>), String>,
+}
+
+impl ListFile {
+    pub fn name(&self) -> &str {
+        list_name(&self.path)
+    }
+}
+
+/// every file in `dir`, sorted by name. each one is read through to count its words
+pub fn scan_lists(dir: &str, preview: usize) -> Result<Vec<ListFile>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("couldn't read {}: {}", dir, e))?;
+
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if !meta.is_file() {
+            continue;
+        }
+
+        let path = format!("{}/{}", dir, entry.file_name().to_string_lossy());
+        let words = words::read_list(&path)
+            .map(|list| {
+                let count = list.words.len();
+                (count, list.words.into_iter().take(preview).collect())
+            })
+            .map_err(|e| e.to_string());
+
+        files.push(ListFile {
+            path,
+            size: meta.len(),
+            words,
+        });
+    }
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// a file size like `4.2 MB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{:.1} {}", size, UNITS[unit]),
+    }
+}
@@ -3,23 +3,49 @@
 // -----------------------------------------------------------------------------
 
 mod app;
+mod cli;
+mod export;
+mod expr;
+mod lists;
+mod pattern;
 mod pred;
+mod preset;
+mod prime;
+mod query;
+mod scrabble;
+mod session;
+mod task;
+mod trie;
 mod tui;
+mod wordle;
 mod words;
 mod ord;
 
+use std::process::ExitCode;
+
 // =============================================================================
 
-fn main() -> std::io::Result<()> {
+fn main() -> std::io::Result<ExitCode> {
+    // any arguments mean a one off search from the command line, which only prints its results
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        return Ok(cli::run(&args));
+    }
+
     colog::basic_builder()
         .filter(None, log::LevelFilter::Debug)
         .init();
     log::info!("logging initialized");
 
     let mut terminal = tui::init()?;
-    let app_result = app::App::default().run(&mut terminal);
+    let mut app = app::App::new();
+    let app_result = app.run(&mut terminal);
     tui::restore()?;
-    app_result
+
+    if let Err(e) = app.save_session() {
+        log::error!("{}", e);
+    }
+    app_result.map(|()| ExitCode::SUCCESS)
 }
 
 // -----------------------------------------------------------------------------
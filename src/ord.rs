@@ -1,23 +1,39 @@
-use std::cmp::Ordering;
+use crate::{lists::Frequency, scrabble};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub enum WordOrder {
     #[default]
     Alphabetical,
     RevAlphabetical,
     Length,
     RevLength,
-    Random,
+    /// Highest scrabble score first
+    Score,
+    /// Lowest scrabble score first
+    RevScore,
+    /// A shuffle that is the same every time for a given seed and word list
+    Random(u64),
+    /// Most common first, going by the frequency column of the word list, so highest count or
+    /// lowest rank. words without one come last
+    Frequency,
 }
 
-pub const ORDER_NAMES: [&str; 4] = [
+pub const ORDER_NAMES: [&str; 8] = [
     "Alphabetical",
     "Reverse Alphabetical",
     "Shortest First",
     "Longest First",
-    // "Random",
+    "Random",
+    "Highest Score",
+    "Lowest Score",
+    "Most Common First",
 ];
- 
+
 impl WordOrder {
     pub fn from_index(index: usize) -> Option<Self> {
         match index {
@@ -25,18 +41,149 @@ impl WordOrder {
             1 => Some(WordOrder::RevAlphabetical),
             2 => Some(WordOrder::Length),
             3 => Some(WordOrder::RevLength),
-            4 => Some(WordOrder::Random),
+            4 => Some(WordOrder::Random(new_seed())),
+            5 => Some(WordOrder::Score),
+            6 => Some(WordOrder::RevScore),
+            7 => Some(WordOrder::Frequency),
             _ => None,
         }
     }
 
-    pub fn cmp(&self, left: &str, right: &str) -> Ordering {
+    /// the inverse of `from_index`
+    pub fn index(&self) -> usize {
+        match self {
+            WordOrder::Alphabetical => 0,
+            WordOrder::RevAlphabetical => 1,
+            WordOrder::Length => 2,
+            WordOrder::RevLength => 3,
+            WordOrder::Random(_) => 4,
+            WordOrder::Score => 5,
+            WordOrder::RevScore => 6,
+            WordOrder::Frequency => 7,
+        }
+    }
+
+    /// whether both are the same kind of order, regardless of seed
+    pub fn same_kind(&self, other: &WordOrder) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    pub fn cmp(
+        &self,
+        left: &str,
+        right: &str,
+        rack: Option<&str>,
+        frequencies: &HashMap<String, Frequency>,
+    ) -> Ordering {
         match self {
             WordOrder::Alphabetical => left.cmp(right),
             WordOrder::RevAlphabetical => left.cmp(right).reverse(),
             WordOrder::Length => left.len().cmp(&right.len()),
             WordOrder::RevLength => left.len().cmp(&right.len()).reverse(),
-            WordOrder::Random => todo!(),
+            WordOrder::Score => scrabble::score(left, rack)
+                .cmp(&scrabble::score(right, rack))
+                .reverse(),
+            WordOrder::RevScore => scrabble::score(left, rack).cmp(&scrabble::score(right, rack)),
+            // `None` is less than any `Some`, so reversing puts the unknown ones last
+            WordOrder::Frequency => frequencies.get(left).cmp(&frequencies.get(right)).reverse(),
+            // a shuffle can't be done with a comparison, see `sort_by_orders`
+            WordOrder::Random(_) => Ordering::Equal,
         }
     }
-}
\ No newline at end of file
+}
+
+impl std::fmt::Display for WordOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WordOrder::Alphabetical => write!(f, "Alphabetical"),
+            WordOrder::RevAlphabetical => write!(f, "Reverse Alphabetical"),
+            WordOrder::Length => write!(f, "Shortest First"),
+            WordOrder::RevLength => write!(f, "Longest First"),
+            WordOrder::Score => write!(f, "Highest Score"),
+            WordOrder::RevScore => write!(f, "Lowest Score"),
+            WordOrder::Frequency => write!(f, "Most Common First"),
+            WordOrder::Random(seed) => write!(f, "Random (seed {})", seed),
+        }
+    }
+}
+
+/// sorts by the first order, breaking ties with the second order and so on. `word` is the word
+/// each item stands for
+///
+/// `rack` is the player's tiles if known, used for scoring, see `scrabble::score`. `frequencies`
+/// is how common each word is, for the lists that say so
+///
+/// once `cancelled` says so every comparison comes out equal, so the sort finishes quickly and
+/// leaves the items in no particular order
+pub fn sort_by_orders<'a, T>(
+    items: &mut [T],
+    word: impl Fn(&T) -> &'a str,
+    orders: &[WordOrder],
+    rack: Option<&str>,
+    frequencies: &HashMap<String, Frequency>,
+    cancelled: &dyn Fn() -> bool,
+) {
+    // a random order breaks every remaining tie, so anything after it doesn't matter. shuffling
+    // first and then doing a stable sort by the orders before it leaves ties shuffled
+    let mut orders = orders;
+    if let Some(i) = orders
+        .iter()
+        .position(|o| matches!(o, WordOrder::Random(_)))
+    {
+        if let WordOrder::Random(seed) = orders[i] {
+            // start from a fixed order so the seed alone decides the result
+            items.sort_by(|a, b| match cancelled() {
+                true => Ordering::Equal,
+                false => word(a).cmp(word(b)),
+            });
+            shuffle(items, seed);
+        }
+        orders = &orders[..i];
+    }
+
+    items.sort_by(|a, b| {
+        if cancelled() {
+            return Ordering::Equal;
+        }
+        orders
+            .iter()
+            .map(|o| o.cmp(word(a), word(b), rack, frequencies))
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+// random ======================================================================
+
+/// a seed short enough to be written down, taken from the clock
+pub fn new_seed() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+    SplitMix64(nanos).next() % 1_000_000
+}
+
+/// fisher-yates shuffle driven by the seed
+fn shuffle<T>(items: &mut [T], seed: u64) {
+    let mut rng = SplitMix64(seed);
+    for i in (1..items.len()).rev() {
+        let j = (rng.next() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
+
+/// small and fast pseudo random number generator, plenty good for shuffling words
+///
+/// see https://prng.di.unimi.it/splitmix64.c
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
@@ -0,0 +1,234 @@
+use crate::pred::InputError;
+
+/// One position of a crossword style pattern
+#[derive(Debug, Clone)]
+enum Token {
+    /// `?` - any single letter
+    Any,
+    /// a specific letter
+    Letter(char),
+    /// `[abc]` any one of the letters, or `[^abc]` any letter except them
+    Class { letters: Vec<char>, negated: bool },
+    /// `*` - any run of letters, including none
+    Star,
+}
+
+impl Token {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            Token::Any => true,
+            Token::Letter(l) => *l == c,
+            Token::Class { letters, negated } => letters.contains(&c) != *negated,
+            Token::Star => true,
+        }
+    }
+}
+
+/// A crossword style pattern such as `c?a??`, `[aeiou]??[^st]` or `un*able`
+///
+/// without a `*` the pattern only matches words of exactly its length. an empty pattern is taken
+/// as `*`, so it doesn't rule anything out until something is typed
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    tokens: Vec<Token>,
+}
+
+impl Pattern {
+    pub fn parse(s: &str) -> Result<Self, InputError> {
+        if s.trim().is_empty() {
+            return Ok(Pattern {
+                tokens: vec![Token::Star],
+            });
+        }
+
+        let mut tokens = Vec::new();
+        let mut chars = s.chars().map(|c| c.to_ascii_lowercase()).enumerate();
+
+        while let Some((i, c)) = chars.next() {
+            let token = match c {
+                '?' => Token::Any,
+                '*' => Token::Star,
+                '[' => {
+                    let mut letters = Vec::new();
+                    let mut negated = false;
+                    let mut closed = false;
+
+                    for (j, c) in chars.by_ref() {
+                        match c {
+                            ']' => {
+                                closed = true;
+                                break;
+                            }
+                            '^' if j == i + 1 => negated = true,
+                            c if c.is_alphabetic() => letters.push(c),
+                            c => {
+                                return Err(InputError::new(j, format!("'{}' is not a letter", c)))
+                            }
+                        }
+                    }
+
+                    if !closed {
+                        return Err(InputError::new(i, "[ is never closed"));
+                    }
+                    if letters.is_empty() {
+                        return Err(InputError::new(i, "[ has no letters"));
+                    }
+
+                    Token::Class { letters, negated }
+                }
+                c if c.is_alphabetic() => Token::Letter(c),
+                c => {
+                    let message = format!("'{}' is not a letter, ?, * or [", c);
+                    return Err(InputError::new(i, message));
+                }
+            };
+
+            // consecutive stars mean the same thing as one
+            if matches!(token, Token::Star) && matches!(tokens.last(), Some(Token::Star)) {
+                continue;
+            }
+            tokens.push(token);
+        }
+
+        Ok(Pattern { tokens })
+    }
+
+    /// the length of every matching word, unless the pattern has a `*`
+    pub fn fixed_len(&self) -> Option<usize> {
+        if self.tokens.iter().any(|t| matches!(t, Token::Star)) {
+            None
+        } else {
+            Some(self.tokens.len())
+        }
+    }
+
+    /// how many positions come before the first `*`, each of them fixed in place
+    pub fn leading(&self) -> usize {
+        self.tokens
+            .iter()
+            .take_while(|t| !matches!(t, Token::Star))
+            .count()
+    }
+
+    /// whether the letter can be at position `i`, for the positions counted by `leading`
+    pub fn allows(&self, i: usize, c: char) -> bool {
+        self.tokens.get(i).is_some_and(|t| t.matches(c))
+    }
+
+    pub fn matches(&self, word: &str) -> bool {
+        if let Some(len) = self.fixed_len() {
+            return word.chars().count() == len
+                && word.chars().zip(&self.tokens).all(|(c, t)| t.matches(c));
+        }
+
+        // usual wildcard matching, remembering the last star so that it can take one more letter
+        // whenever the rest of the pattern fails to match
+        let word: Vec<char> = word.chars().collect();
+        let (mut w, mut t) = (0, 0);
+        let mut last_star: Option<(usize, usize)> = None;
+
+        while w < word.len() {
+            match self.tokens.get(t) {
+                Some(Token::Star) => {
+                    last_star = Some((t, w));
+                    t += 1;
+                }
+                Some(token) if token.matches(word[w]) => {
+                    w += 1;
+                    t += 1;
+                }
+                _ => match last_star {
+                    Some((star_t, star_w)) => {
+                        last_star = Some((star_t, star_w + 1));
+                        t = star_t + 1;
+                        w = star_w + 1;
+                    }
+                    None => return false,
+                },
+            }
+        }
+
+        self.tokens[t..].iter().all(|t| matches!(t, Token::Star))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Symbol {
+    /// a lowercase letter standing for itself
+    Fixed(char),
+    /// a capital letter, by its index in the alphabet
+    Placeholder(usize),
+}
+
+/// A cryptogram style pattern such as `ABBA` or `aBBc`
+///
+/// capital letters are placeholders, every occurrence of one stands for the same letter and
+/// different placeholders stand for different letters. lowercase letters stand for themselves and
+/// no placeholder can stand for one of them, so `ABBA` matches "otto" and `aBBc` doesn't match
+/// "aaac". an empty shape matches any word
+#[derive(Debug, Clone)]
+pub struct LetterShape {
+    symbols: Vec<Symbol>,
+    fixed: Vec<char>,
+}
+
+impl LetterShape {
+    pub fn parse(s: &str) -> Result<Self, InputError> {
+        if s.trim().is_empty() {
+            return Ok(LetterShape {
+                symbols: Vec::new(),
+                fixed: Vec::new(),
+            });
+        }
+
+        let symbols = s
+            .chars()
+            .enumerate()
+            .map(|(i, c)| match c {
+                'A'..='Z' => Ok(Symbol::Placeholder(c as usize - 'A' as usize)),
+                c if c.is_lowercase() => Ok(Symbol::Fixed(c)),
+                c => Err(InputError::new(i, format!("'{}' is not a letter", c))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let fixed = symbols
+            .iter()
+            .filter_map(|s| match s {
+                Symbol::Fixed(c) => Some(*c),
+                Symbol::Placeholder(_) => None,
+            })
+            .collect();
+
+        Ok(LetterShape { symbols, fixed })
+    }
+
+    pub fn matches(&self, word: &str) -> bool {
+        if self.symbols.is_empty() {
+            return true;
+        }
+        if word.chars().count() != self.symbols.len() {
+            return false;
+        }
+
+        let mut mapping: [Option<char>; 26] = [None; 26];
+        let mut used = self.fixed.clone();
+
+        for (c, symbol) in word.chars().zip(&self.symbols) {
+            match symbol {
+                Symbol::Fixed(letter) if *letter != c => return false,
+                Symbol::Fixed(_) => {}
+                Symbol::Placeholder(p) => match mapping[*p] {
+                    Some(letter) if letter != c => return false,
+                    Some(_) => {}
+                    None if used.contains(&c) => return false,
+                    None => {
+                        mapping[*p] = Some(c);
+                        used.push(c);
+                    }
+                },
+            }
+        }
+
+        true
+    }
+}
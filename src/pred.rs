@@ -1,6 +1,36 @@
-#[derive(Debug)]
+use crate::{
+    lists::WordInfo,
+    pattern::{LetterShape, Pattern},
+    prime::LetterBag,
+    scrabble,
+    wordle::{self, WordleGuess},
+};
+use regex::Regex;
+
+/// Why the input of a predicate can't be used, and where in it the problem is
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputError {
+    /// index of the offending character, counted in chars
+    pub pos: usize,
+    pub message: String,
+}
+
+impl InputError {
+    pub fn new(pos: usize, message: impl Into<String>) -> Self {
+        InputError {
+            pos,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum WordPredicate {
-    Length(usize),
+    /// Find words of exactly this many letters, any length if it's left empty
+    Length {
+        input: String,
+        len: Result<Option<usize>, String>,
+    },
     StartsWith(String),
     EndsWith(String),
     Contains(String),
@@ -12,38 +42,201 @@ pub enum WordPredicate {
     ///
     /// `tiles` must be a string containing only letters and possibly question marks to represent blank tiles
     ScrabblePlayable(String),
+    /// Find words matching a regular expression
+    ///
+    /// the pattern is compiled once when it is updated. if `anchored` is set the pattern has to
+    /// match the whole word, otherwise a match anywhere in the word is enough
+    Regex {
+        pattern: String,
+        anchored: bool,
+        compiled: Result<Regex, String>,
+    },
+    /// Find words that are still possible wordle answers after the given guesses
+    ///
+    /// `input` holds rows like `crane:gy..y` separated by spaces or commas, see `WordleGuess::parse`
+    Wordle {
+        input: String,
+        guesses: Result<Vec<WordleGuess>, InputError>,
+    },
+    /// Find words fitting a crossword style pattern, e.g. `c?a??`, `[aeiou]*` or `[^st]?*ing`
+    ///
+    /// without a `*` the pattern also fixes the length of the word, see `Pattern`
+    Pattern {
+        input: String,
+        parsed: Result<Pattern, InputError>,
+    },
+    /// Find words that use exactly the given letters, each as many times as it is given
+    ///
+    /// `bag` is `None` while no letters are given, which matches any word
+    Anagram {
+        letters: String,
+        bag: Result<Option<LetterBag>, String>,
+    },
+    /// Find words that can be spelled from the given letters, each used at most as many times as
+    /// it is given, any word while no letters are given
+    SubAnagram {
+        letters: String,
+        bag: Result<Option<LetterBag>, String>,
+    },
+    /// Find words with a given pattern of repeated letters, e.g. `ABBA` for "otto" or `ABCBA`
+    /// for "level", with lowercase letters fixed in place, see `LetterShape`
+    Shape {
+        input: String,
+        parsed: Result<LetterShape, InputError>,
+    },
+    /// Find words whose scrabble score is in the given range, like `10-20`, `10-`, `-8` or `12`
+    ///
+    /// this is the face value of the tiles, without blanks or board bonuses
+    ScrabbleScore {
+        input: String,
+        range: Result<(u32, u32), String>,
+    },
+    /// Find words that came from the given list, named by its file without the extension, e.g.
+    /// `dictionary`, see `lists`
+    FromList(String),
+    /// Find words used at least this often, going by the frequency column of the word list. for
+    /// a list of ranks, the words ranked this or better
+    ///
+    /// words without a frequency only match a minimum of 0
+    MinFrequency {
+        input: String,
+        min: Result<u64, String>,
+    },
 }
 
-pub const PREDICATE_NAMES: [&str; 6] = [
+pub const PREDICATE_NAMES: [&str; 16] = [
     "Length",
     "Starts with",
     "Ends with",
     "Contains",
     "Using letters",
     "Scrabble playable",
+    "Regex",
+    "Regex (whole word)",
+    "Wordle feedback",
+    "Pattern",
+    "Anagram of",
+    "Sub-anagram of",
+    "Letter shape",
+    "Scrabble score",
+    "From list",
+    "Min frequency",
 ];
 
-impl WordPredicate {
+/// short names for the predicates in `PREDICATE_NAMES` used by the query language, see `query`
+pub const PREDICATE_KEYS: [&str; 16] = [
+    "len",
+    "start",
+    "end",
+    "contains",
+    "using",
+    "rack",
+    "regex",
+    "fullregex",
+    "wordle",
+    "pattern",
+    "anagram",
+    "subanagram",
+    "shape",
+    "score",
+    "from",
+    "freq",
+];
 
+impl WordPredicate {
     pub fn from_index(index: usize) -> Option<Self> {
         match index {
-            0 => Some(WordPredicate::Length(0)),
+            0 => Some(WordPredicate::Length {
+                input: String::new(),
+                len: parse_length(""),
+            }),
             1 => Some(WordPredicate::StartsWith(String::new())),
             2 => Some(WordPredicate::EndsWith(String::new())),
             3 => Some(WordPredicate::Contains(String::new())),
             4 => Some(WordPredicate::UsingLetters(String::new())),
             5 => Some(WordPredicate::ScrabblePlayable(String::new())),
+            6 => Some(WordPredicate::new_regex("", false)),
+            7 => Some(WordPredicate::new_regex("", true)),
+            8 => Some(WordPredicate::Wordle {
+                input: String::new(),
+                guesses: Ok(Vec::new()),
+            }),
+            9 => Some(WordPredicate::Pattern {
+                input: String::new(),
+                parsed: Pattern::parse(""),
+            }),
+            10 => Some(WordPredicate::Anagram {
+                letters: String::new(),
+                bag: letter_bag(""),
+            }),
+            11 => Some(WordPredicate::SubAnagram {
+                letters: String::new(),
+                bag: letter_bag(""),
+            }),
+            12 => Some(WordPredicate::Shape {
+                input: String::new(),
+                parsed: LetterShape::parse(""),
+            }),
+            13 => Some(WordPredicate::ScrabbleScore {
+                input: String::new(),
+                range: scrabble::parse_score_range(""),
+            }),
+            14 => Some(WordPredicate::FromList(String::new())),
+            15 => Some(WordPredicate::MinFrequency {
+                input: String::new(),
+                min: parse_frequency(""),
+            }),
             _ => None,
         }
     }
 
-    pub fn matches(&self, word: &str) -> bool {
+    /// the inverse of `from_index`
+    pub fn index(&self) -> usize {
         match self {
-            WordPredicate::Length(len) => word.len() == *len,
+            WordPredicate::Length { .. } => 0,
+            WordPredicate::StartsWith(_) => 1,
+            WordPredicate::EndsWith(_) => 2,
+            WordPredicate::Contains(_) => 3,
+            WordPredicate::UsingLetters(_) => 4,
+            WordPredicate::ScrabblePlayable(_) => 5,
+            WordPredicate::Regex { anchored, .. } => {
+                if *anchored {
+                    7
+                } else {
+                    6
+                }
+            }
+            WordPredicate::Wordle { .. } => 8,
+            WordPredicate::Pattern { .. } => 9,
+            WordPredicate::Anagram { .. } => 10,
+            WordPredicate::SubAnagram { .. } => 11,
+            WordPredicate::Shape { .. } => 12,
+            WordPredicate::ScrabbleScore { .. } => 13,
+            WordPredicate::FromList(_) => 14,
+            WordPredicate::MinFrequency { .. } => 15,
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        PREDICATE_KEYS[self.index()]
+    }
+
+    /// `info` is only needed for `FromList` and `MinFrequency`
+    pub fn matches(&self, word: &str, info: WordInfo) -> bool {
+        match self {
+            WordPredicate::Length { len, .. } => match len {
+                Ok(Some(len)) => word.len() == *len,
+                Ok(None) => true,
+                Err(_) => false,
+            },
             WordPredicate::StartsWith(prefix) => word.starts_with(prefix),
             WordPredicate::EndsWith(suffix) => word.ends_with(suffix),
             WordPredicate::Contains(substring) => word.contains(substring),
             WordPredicate::UsingLetters(letters) => {
+                if let Some(signature) = info.letters {
+                    return signature.only_uses(letters);
+                }
+
                 for l in word.chars() {
                     if !letters.contains(l) {
                         return false;
@@ -52,6 +245,10 @@ impl WordPredicate {
                 true
             }
             WordPredicate::ScrabblePlayable(tiles) => {
+                if let Some(signature) = info.letters {
+                    return signature.playable_with(tiles);
+                }
+
                 let mut tiles = tiles.to_string();
 
                 for l in word.chars() {
@@ -66,28 +263,109 @@ impl WordPredicate {
 
                 true
             }
+            WordPredicate::Regex { compiled, .. } => match compiled {
+                Ok(re) => re.is_match(word),
+                Err(_) => false,
+            },
+            WordPredicate::Wordle { guesses, .. } => match guesses {
+                Ok(guesses) => guesses.iter().all(|g| g.allows(word)),
+                Err(_) => false,
+            },
+            WordPredicate::Pattern { parsed, .. } => match parsed {
+                Ok(pattern) => pattern.matches(word),
+                Err(_) => false,
+            },
+            WordPredicate::Anagram { bag, .. } => match bag {
+                Ok(None) => true,
+                Ok(Some(bag)) => match (info.letters, bag.signature()) {
+                    (Some(word_letters), Some(letters)) => word_letters == letters,
+                    _ => bag.is_anagram(word),
+                },
+                Err(_) => false,
+            },
+            WordPredicate::SubAnagram { bag, .. } => match bag {
+                Ok(None) => true,
+                Ok(Some(bag)) => match (info.letters, bag.signature()) {
+                    (Some(word_letters), Some(letters)) => word_letters.fits_in(letters),
+                    _ => bag.is_sub_anagram(word),
+                },
+                Err(_) => false,
+            },
+            WordPredicate::Shape { parsed, .. } => match parsed {
+                Ok(shape) => shape.matches(word),
+                Err(_) => false,
+            },
+            WordPredicate::ScrabbleScore { range, .. } => match range {
+                Ok((min, max)) => (*min..=*max).contains(&scrabble::score(word, None)),
+                Err(_) => false,
+            },
+            WordPredicate::FromList(name) => name.is_empty() || info.from.contains(name),
+            WordPredicate::MinFrequency { min, .. } => match min {
+                Ok(0) => true,
+                Ok(min) => info.frequency.is_some_and(|f| f.at_least(*min)),
+                Err(_) => false,
+            },
         }
     }
 
     pub fn get_string(&self) -> String {
         match self {
-            WordPredicate::Length(len) => len.to_string(),
+            WordPredicate::Length { input, .. } => input.to_string(),
             WordPredicate::StartsWith(prefix) => prefix.to_string(),
             WordPredicate::EndsWith(suffix) => suffix.to_string(),
             WordPredicate::Contains(substring) => substring.to_string(),
             WordPredicate::UsingLetters(letters) => letters.to_string(),
             WordPredicate::ScrabblePlayable(tiles) => tiles.to_string(),
+            WordPredicate::Regex { pattern, .. } => pattern.to_string(),
+            WordPredicate::Wordle { input, .. } => input.to_string(),
+            WordPredicate::Pattern { input, .. } => input.to_string(),
+            WordPredicate::Anagram { letters, .. } => letters.to_string(),
+            WordPredicate::SubAnagram { letters, .. } => letters.to_string(),
+            WordPredicate::Shape { input, .. } => input.to_string(),
+            WordPredicate::ScrabbleScore { input, .. } => input.to_string(),
+            WordPredicate::FromList(name) => name.to_string(),
+            WordPredicate::MinFrequency { input, .. } => input.to_string(),
+        }
+    }
+
+    /// the reason this predicate can't be used as entered, if any
+    pub fn get_error(&self) -> Option<&str> {
+        match self {
+            WordPredicate::Regex {
+                compiled: Err(e), ..
+            } => Some(e),
+            WordPredicate::Wordle {
+                guesses: Err(e), ..
+            } => Some(&e.message),
+            WordPredicate::Pattern { parsed: Err(e), .. } => Some(&e.message),
+            WordPredicate::Anagram { bag: Err(e), .. } => Some(e),
+            WordPredicate::SubAnagram { bag: Err(e), .. } => Some(e),
+            WordPredicate::Shape { parsed: Err(e), .. } => Some(&e.message),
+            WordPredicate::ScrabbleScore { range: Err(e), .. } => Some(e),
+            WordPredicate::MinFrequency { min: Err(e), .. } => Some(e),
+            WordPredicate::Length { len: Err(e), .. } => Some(e),
+            _ => None,
+        }
+    }
+
+    /// where in the input the problem `get_error` describes is, counted in chars, for the
+    /// predicates that can tell
+    pub fn get_error_pos(&self) -> Option<usize> {
+        match self {
+            WordPredicate::Wordle {
+                guesses: Err(e), ..
+            }
+            | WordPredicate::Pattern { parsed: Err(e), .. }
+            | WordPredicate::Shape { parsed: Err(e), .. } => Some(e.pos),
+            _ => None,
         }
     }
 
     pub fn update(&mut self, s: &str) {
         match self {
-            WordPredicate::Length(len) => {
-                if let Ok(len2) = s.parse() {
-                    *len = len2;
-                } else {
-                    *len = 0;
-                }
+            WordPredicate::Length { input, len } => {
+                *input = s.to_string();
+                *len = parse_length(s);
             }
             WordPredicate::StartsWith(prefix) => {
                 *prefix = s.to_string();
@@ -104,6 +382,141 @@ impl WordPredicate {
             WordPredicate::ScrabblePlayable(tiles) => {
                 *tiles = s.to_string();
             }
+            WordPredicate::Regex { anchored, .. } => {
+                *self = WordPredicate::new_regex(s, *anchored);
+            }
+            WordPredicate::Wordle { input, guesses } => {
+                *input = s.to_string();
+                *guesses = wordle::parse_guesses(s);
+            }
+            WordPredicate::Pattern { input, parsed } => {
+                *input = s.to_string();
+                *parsed = Pattern::parse(s);
+            }
+            WordPredicate::Anagram { letters, bag }
+            | WordPredicate::SubAnagram { letters, bag } => {
+                *letters = s.to_string();
+                *bag = letter_bag(s);
+            }
+            WordPredicate::Shape { input, parsed } => {
+                *input = s.to_string();
+                *parsed = LetterShape::parse(s);
+            }
+            WordPredicate::ScrabbleScore { input, range } => {
+                *input = s.to_string();
+                *range = scrabble::parse_score_range(s);
+            }
+            WordPredicate::FromList(name) => {
+                *name = s.to_string();
+            }
+            WordPredicate::MinFrequency { input, min } => {
+                *input = s.to_string();
+                *min = parse_frequency(s);
+            }
+        }
+    }
+
+    /// whether every word this matches was also matched by `old`, the same predicate before an
+    /// edit, so the words it matched can be filtered again instead of the whole list. only the
+    /// common cases of typing more are caught, anything else is `false`
+    pub fn narrows(&self, old: &WordPredicate) -> bool {
+        // something that doesn't parse matches nothing
+        if old.get_error().is_some() {
+            return false;
+        }
+        if self.get_error().is_some() {
+            return true;
+        }
+
+        let (new, old_s) = (self.get_string(), old.get_string());
+        match (self, old) {
+            (WordPredicate::StartsWith(_), WordPredicate::StartsWith(_)) => new.starts_with(&old_s),
+            (WordPredicate::EndsWith(_), WordPredicate::EndsWith(_)) => new.ends_with(&old_s),
+            (WordPredicate::Contains(_), WordPredicate::Contains(_)) => new.contains(&old_s),
+            // fewer letters to choose from
+            (WordPredicate::UsingLetters(_), WordPredicate::UsingLetters(_)) => {
+                new.chars().all(|c| old_s.contains(c))
+            }
+            // letters given where there weren't any
+            (WordPredicate::Anagram { .. }, WordPredicate::Anagram { bag: Ok(None), .. })
+            | (WordPredicate::SubAnagram { .. }, WordPredicate::SubAnagram { bag: Ok(None), .. }) => {
+                true
+            }
+            // fewer tiles
+            (WordPredicate::ScrabblePlayable(_), WordPredicate::ScrabblePlayable(_))
+            | (WordPredicate::SubAnagram { .. }, WordPredicate::SubAnagram { .. }) => {
+                let mut tiles = old_s;
+                new.chars().all(|c| match tiles.find(c) {
+                    Some(pos) => {
+                        tiles.remove(pos);
+                        true
+                    }
+                    None => false,
+                })
+            }
+            // another guess added after the old ones
+            (WordPredicate::Wordle { .. }, WordPredicate::Wordle { .. }) => {
+                old_s.trim().is_empty()
+                    || new
+                        .strip_prefix(old_s.trim_end())
+                        .is_some_and(|rest| rest.starts_with([' ', ',']))
+            }
+            // a length given where there wasn't one
+            (
+                WordPredicate::Length { len: Ok(_), .. },
+                WordPredicate::Length { len: Ok(None), .. },
+            ) => true,
+            // a higher minimum rules words out with counts but lets more in with ranks
+            (
+                WordPredicate::MinFrequency { min: Ok(new), .. },
+                WordPredicate::MinFrequency { min: Ok(old), .. },
+            ) => *old == 0 || new == old,
+            _ => false,
+        }
+    }
+
+    fn new_regex(pattern: &str, anchored: bool) -> Self {
+        let source = if anchored {
+            format!("^(?:{})$", pattern)
+        } else {
+            pattern.to_string()
+        };
+
+        // the full error message repeats the pattern over several lines, the last one is the
+        // part worth showing, without the `error: ` it starts with
+        let compiled = Regex::new(&source).map_err(|e| match e {
+            regex::Error::Syntax(msg) => {
+                let last = msg.lines().last().unwrap_or_default();
+                last.strip_prefix("error: ").unwrap_or(last).to_string()
+            }
+            e => e.to_string(),
+        });
+
+        WordPredicate::Regex {
+            pattern: pattern.to_string(),
+            anchored,
+            compiled,
+        }
+    }
+
+    /// roughly how long checking a word takes compared to the other predicates, used to decide
+    /// which to check first, see `expr::plan`
+    pub fn cost(&self) -> f64 {
+        match self {
+            WordPredicate::Length { .. } | WordPredicate::MinFrequency { .. } => 1.0,
+            WordPredicate::StartsWith(_) | WordPredicate::EndsWith(_) => 1.5,
+            WordPredicate::FromList(_) => 2.0,
+            // these go through the precomputed letter counts
+            WordPredicate::UsingLetters(_)
+            | WordPredicate::Anagram { .. }
+            | WordPredicate::SubAnagram { .. } => 2.0,
+            WordPredicate::Contains(_) => 3.0,
+            WordPredicate::ScrabblePlayable(_) => 4.0,
+            WordPredicate::Pattern { .. } => 4.0,
+            WordPredicate::Shape { .. } => 6.0,
+            WordPredicate::ScrabbleScore { .. } => 6.0,
+            WordPredicate::Wordle { .. } => 10.0,
+            WordPredicate::Regex { .. } => 20.0,
         }
     }
 }
@@ -111,12 +524,144 @@ impl WordPredicate {
 impl std::fmt::Display for WordPredicate {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            WordPredicate::Length(len) => write!(f, "Length: {}", len),
+            WordPredicate::Length { input, .. } => write!(f, "Length: {}", input),
             WordPredicate::StartsWith(prefix) => write!(f, "Starts with: {}", prefix),
             WordPredicate::EndsWith(suffix) => write!(f, "Ends with: {}", suffix),
             WordPredicate::Contains(substring) => write!(f, "Contains: {}", substring),
             WordPredicate::UsingLetters(letters) => write!(f, "Using letters: {}", letters),
             WordPredicate::ScrabblePlayable(tiles) => write!(f, "Scrabble playable: {}", tiles),
+            WordPredicate::Regex {
+                pattern, anchored, ..
+            } => {
+                if *anchored {
+                    write!(f, "Regex (whole word): {}", pattern)
+                } else {
+                    write!(f, "Regex: {}", pattern)
+                }
+            }
+            WordPredicate::Wordle { input, .. } => write!(f, "Wordle feedback: {}", input),
+            WordPredicate::Pattern { input, .. } => write!(f, "Pattern: {}", input),
+            WordPredicate::Anagram { letters, .. } => write!(f, "Anagram of: {}", letters),
+            WordPredicate::SubAnagram { letters, .. } => write!(f, "Sub-anagram of: {}", letters),
+            WordPredicate::Shape { input, .. } => write!(f, "Letter shape: {}", input),
+            WordPredicate::ScrabbleScore { input, .. } => write!(f, "Scrabble score: {}", input),
+            WordPredicate::FromList(name) => write!(f, "From list: {}", name),
+            WordPredicate::MinFrequency { input, .. } => write!(f, "Min frequency: {}", input),
         }
     }
 }
+
+/// the letters of an anagram, empty means any word
+fn letter_bag(s: &str) -> Result<Option<LetterBag>, String> {
+    if s.trim().is_empty() {
+        return Ok(None);
+    }
+    LetterBag::new(s).map(Some)
+}
+
+/// a number of letters, empty means any length
+fn parse_length(s: &str) -> Result<Option<usize>, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    s.parse()
+        .map(Some)
+        .map_err(|_| format!("\"{}\" is not a length", s))
+}
+
+/// a count like `1000`, empty means no minimum
+fn parse_frequency(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(0);
+    }
+    s.parse().map_err(|_| format!("\"{}\" is not a count", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lists::Provenance, prime::Signature, words};
+
+    fn predicate(key: &str, s: &str) -> WordPredicate {
+        let index = PREDICATE_KEYS.iter().position(|k| *k == key).unwrap();
+        let mut p = WordPredicate::from_index(index).unwrap();
+        p.update(s);
+        p
+    }
+
+    /// whether editing `old` into `new` narrows, making sure when it does that filtering the old
+    /// matches again finds the same words as filtering the whole list
+    fn narrows(key: &str, old: &str, new: &str) -> bool {
+        let (old, new) = (predicate(key, old), predicate(key, new));
+        if !new.narrows(&old) {
+            return false;
+        }
+
+        for word in words::read_list("./lists/answerlist.txt").unwrap().words {
+            let signature = Signature::new(&word);
+            let info = WordInfo {
+                from: Provenance {
+                    lists: &[],
+                    mask: 0,
+                },
+                frequency: None,
+                letters: signature.as_ref(),
+            };
+            if new.matches(&word, info) {
+                assert!(
+                    old.matches(&word, info),
+                    "{} matches {} but not {}",
+                    word,
+                    new,
+                    old
+                );
+            }
+        }
+        true
+    }
+
+    #[test]
+    fn typing_more_narrows() {
+        assert!(narrows("start", "ab", "abo"));
+        assert!(narrows("end", "ng", "ing"));
+        assert!(narrows("contains", "ea", "eat"));
+        assert!(narrows("wordle", "crane:gy..y", "crane:gy..y doubt:..y.."));
+        assert!(narrows("wordle", "", "crane:....."));
+    }
+
+    #[test]
+    fn fewer_letters_narrows() {
+        assert!(narrows("using", "abcde", "abc"));
+        assert!(narrows("rack", "aeinrst", "aeinst"));
+        assert!(narrows("rack", "ab??", "ab?"));
+        assert!(narrows("subanagram", "retains", "rains"));
+    }
+
+    #[test]
+    fn filling_in_an_empty_predicate_narrows() {
+        assert!(narrows("len", "", "5"));
+        assert!(narrows("freq", "", "100"));
+        assert!(narrows("anagram", "", "crane"));
+        assert!(narrows("subanagram", "", "crane"));
+    }
+
+    #[test]
+    fn errors_match_nothing() {
+        assert!(narrows("pattern", "c?a??", "c?a["));
+        assert!(!narrows("pattern", "c?a[", "c?a??"));
+    }
+
+    #[test]
+    fn widening_doesnt_narrow() {
+        assert!(!narrows("start", "abo", "ab"));
+        assert!(!narrows("using", "abc", "abcd"));
+        assert!(!narrows("rack", "ab", "ab?"));
+        assert!(!narrows("subanagram", "rains", "retains"));
+        assert!(!narrows("wordle", "crane:gy..y", "crane:gy..."));
+        assert!(!narrows("len", "5", "6"));
+        // with ranks a higher minimum lets more words in
+        assert!(!narrows("freq", "100", "200"));
+    }
+}
@@ -0,0 +1,171 @@
+//! Named searches saved to a plain text file, one section per preset:
+//!
+//! ```text
+//! [wordle baseline]
+//! list = ./lists/wordlist.txt
+//! query = len:5 !contains:e
+//! sort = Highest Score; Alphabetical
+//! seed = 42
+//! ```
+
+use crate::{ord::ORDER_NAMES, query, words::WordFinder};
+use std::io;
+
+/// where presets are kept, next to the lists folder
+pub const PRESETS_PATH: &str = "./presets.txt";
+
+/// A saved `WordFinder` configuration
+#[derive(Debug, Clone)]
+pub struct Preset {
+    pub name: String,
+    pub file_path: String,
+    /// the predicates in the query language, see `query`
+    pub query: String,
+    /// indices into `ORDER_NAMES`, highest priority first
+    pub orders: Vec<usize>,
+    pub seed: Option<u64>,
+}
+
+impl Preset {
+    pub fn new(name: &str) -> Self {
+        Preset {
+            name: name.to_string(),
+            file_path: String::new(),
+            query: String::new(),
+            orders: Vec::new(),
+            seed: None,
+        }
+    }
+
+    pub fn from_finder(name: &str, finder: &WordFinder) -> Self {
+        Preset {
+            name: name.to_string(),
+            file_path: finder.file_path.clone(),
+            query: finder.get_query(),
+            orders: finder.word_orders.iter().map(|o| o.index()).collect(),
+            seed: finder.seed(),
+        }
+    }
+
+    /// sets the predicates and orders of the finder, leaving loading the word list to the caller
+    /// so it can deal with the result. loading sorts, so nothing is sorted here
+    pub fn apply(&self, finder: &mut WordFinder) -> Result<(), String> {
+        // not `set_query`, which sorts again when the rack changes
+        finder.predicates =
+            query::parse(&self.query).map_err(|e| format!("preset \"{}\": {}", self.name, e))?;
+        finder.invalidate();
+        finder.set_orders(&self.orders, self.seed);
+        Ok(())
+    }
+
+    /// sets one of the `key = value` lines of the file format
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "list" => self.file_path = value.to_string(),
+            "query" => self.query = value.to_string(),
+            "sort" => {
+                for name in value.split(';').map(|n| n.trim()).filter(|n| !n.is_empty()) {
+                    match ORDER_NAMES
+                        .iter()
+                        .position(|o| o.eq_ignore_ascii_case(name))
+                    {
+                        Some(index) => self.orders.push(index),
+                        None => return Err(format!("unknown sorting \"{}\"", name)),
+                    }
+                }
+            }
+            "seed" => match value.parse() {
+                Ok(seed) => self.seed = Some(seed),
+                Err(_) => return Err(format!("\"{}\" is not a seed", value)),
+            },
+            key => return Err(format!("unknown setting \"{}\"", key)),
+        }
+        Ok(())
+    }
+
+    /// the `key = value` lines of the file format
+    pub fn settings(&self) -> String {
+        let sort: Vec<&str> = self.orders.iter().map(|i| ORDER_NAMES[*i]).collect();
+
+        let mut settings = format!(
+            "list = {}\nquery = {}\nsort = {}\n",
+            self.file_path,
+            self.query,
+            sort.join("; ")
+        );
+        if let Some(seed) = self.seed {
+            settings.push_str(&format!("seed = {}\n", seed));
+        }
+        settings
+    }
+}
+
+/// reads every preset in the file, a missing file just means none have been saved yet
+pub fn load_presets(path: &str) -> Result<Vec<Preset>, String> {
+    let file = match std::fs::read_to_string(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("couldn't read {}: {}", path, e)),
+    };
+
+    let mut presets: Vec<Preset> = Vec::new();
+
+    for (i, line) in file.lines().enumerate() {
+        let line = line.trim();
+        let error = |message: String| Err(format!("{} line {}: {}", path, i + 1, message));
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            presets.push(Preset::new(name.trim()));
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return error(format!("expected key = value or [name], got \"{}\"", line));
+        };
+        let Some(preset) = presets.last_mut() else {
+            return error("settings before the first [name]".to_string());
+        };
+        if let Err(e) = preset.set(key.trim(), value.trim()) {
+            return error(e);
+        }
+    }
+
+    Ok(presets)
+}
+
+pub fn write_presets(path: &str, presets: &[Preset]) -> Result<(), String> {
+    let mut file = String::new();
+
+    for (i, preset) in presets.iter().enumerate() {
+        if i > 0 {
+            file.push('\n');
+        }
+
+        file.push_str(&format!("[{}]\n", preset.name));
+        file.push_str(&preset.settings());
+    }
+
+    std::fs::write(path, file).map_err(|e| format!("couldn't write {}: {}", path, e))
+}
+
+/// adds the preset to the file, replacing any with the same name
+pub fn save_preset(path: &str, preset: Preset) -> Result<(), String> {
+    let mut presets = load_presets(path)?;
+
+    match presets.iter_mut().find(|p| p.name == preset.name) {
+        Some(p) => *p = preset,
+        None => presets.push(preset),
+    }
+
+    write_presets(path, &presets)
+}
+
+pub fn delete_preset(path: &str, name: &str) -> Result<(), String> {
+    let mut presets = load_presets(path)?;
+    presets.retain(|p| p.name != name);
+    write_presets(path, &presets)
+}
@@ -28,12 +28,152 @@ const PRIMES: [u64; 26] = [
     101, // z
 ];
 
-fn letter_to_index(letter: char) -> usize {
-    letter as usize - 'a' as usize
+/// where a-z is in the alphabet, counting from 0
+pub(crate) fn letter_to_index(letter: char) -> Option<usize> {
+    letter
+        .is_ascii_lowercase()
+        .then(|| letter as usize - 'a' as usize)
 }
 
-fn prime_encoding(word: &str) -> u64 {
-    word.chars()
-        .map(|letter| PRIMES[letter_to_index(letter)])
-        .product()
+/// the product of the primes of each letter, so two words have the same encoding exactly when
+/// they are anagrams
+///
+/// `None` if the word has letters other than a-z, or is long enough to overflow a `u64`
+pub fn prime_encoding(word: &str) -> Option<u64> {
+    word.chars().try_fold(1u64, |acc, letter| {
+        acc.checked_mul(PRIMES[letter_to_index(letter)?])
+    })
+}
+
+/// how many times each of the letters a-z appears in the word, `None` if it has any other letters
+pub fn letter_counts(word: &str) -> Option<[u32; 26]> {
+    let mut counts = [0; 26];
+    for letter in word.chars() {
+        counts[letter_to_index(letter)?] += 1;
+    }
+    Some(counts)
+}
+
+fn letter_mask(letters: &str) -> u32 {
+    letters
+        .chars()
+        .filter_map(letter_to_index)
+        .fold(0, |mask, i| mask | 1 << i)
+}
+
+/// How many times each of the letters a-z is in a word, worked out once when a list is loaded so
+/// the predicates about letters don't have to go through the word again
+///
+/// words with the same signature are anagrams of each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Signature {
+    counts: [u8; 26],
+    /// a bit for each letter that's in the word
+    mask: u32,
+}
+
+impl Signature {
+    /// `None` if the word has letters other than a-z, or more than 255 of one of them
+    pub fn new(word: &str) -> Option<Self> {
+        let mut counts = [0u8; 26];
+        let mut mask = 0;
+        for letter in word.chars() {
+            let i = letter_to_index(letter)?;
+            counts[i] = counts[i].checked_add(1)?;
+            mask |= 1 << i;
+        }
+        Some(Signature { counts, mask })
+    }
+
+    /// how many of each letter
+    pub fn counts(&self) -> [u8; 26] {
+        self.counts
+    }
+
+    /// whether every letter of the word is one of `letters`
+    pub fn only_uses(&self, letters: &str) -> bool {
+        self.mask & !letter_mask(letters) == 0
+    }
+
+    /// whether the word can be spelled with the tiles, where each `?` is a blank
+    pub fn playable_with(&self, tiles: &str) -> bool {
+        let mut have = [0u32; 26];
+        let mut blanks = 0;
+        for tile in tiles.chars() {
+            match letter_to_index(tile) {
+                Some(i) => have[i] += 1,
+                None if tile == '?' => blanks += 1,
+                None => {}
+            }
+        }
+
+        let missing: u32 = self
+            .counts
+            .iter()
+            .zip(have)
+            .map(|(count, have)| (*count as u32).saturating_sub(have))
+            .sum();
+        missing <= blanks
+    }
+
+    /// whether the word has each letter at most as many times as `other`
+    pub fn fits_in(&self, other: &Signature) -> bool {
+        self.mask & !other.mask == 0 && self.counts.iter().zip(&other.counts).all(|(a, b)| a <= b)
+    }
+}
+
+/// A bag of letters that words can be checked against for anagrams
+///
+/// comparisons go through the prime encoding when both sides fit in a `u64`, and fall back to
+/// counting letters for anything longer
+#[derive(Debug, Clone)]
+pub struct LetterBag {
+    len: usize,
+    encoding: Option<u64>,
+    counts: [u32; 26],
+    signature: Option<Signature>,
+}
+
+impl LetterBag {
+    pub fn new(letters: &str) -> Result<Self, String> {
+        let letters = letters.to_lowercase();
+        let counts = letter_counts(&letters).ok_or("only the letters a-z can be used")?;
+
+        Ok(LetterBag {
+            len: letters.len(),
+            encoding: prime_encoding(&letters),
+            counts,
+            signature: Signature::new(&letters),
+        })
+    }
+
+    /// the letters as a word signature, to compare with the ones worked out for the word list
+    pub fn signature(&self) -> Option<&Signature> {
+        self.signature.as_ref()
+    }
+
+    /// whether the word uses exactly these letters
+    pub fn is_anagram(&self, word: &str) -> bool {
+        if word.len() != self.len {
+            return false;
+        }
+
+        match (self.encoding, prime_encoding(word)) {
+            (Some(a), Some(b)) => a == b,
+            _ => letter_counts(word) == Some(self.counts),
+        }
+    }
+
+    /// whether the word can be spelled from these letters, using each one at most once
+    pub fn is_sub_anagram(&self, word: &str) -> bool {
+        if word.len() > self.len {
+            return false;
+        }
+
+        match (self.encoding, prime_encoding(word)) {
+            (Some(a), Some(b)) => a % b == 0,
+            _ => letter_counts(word)
+                .is_some_and(|counts| counts.iter().zip(&self.counts).all(|(w, l)| w <= l)),
+        }
+    }
 }
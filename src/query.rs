@@ -0,0 +1,314 @@
+//! A text form of the predicate tree, e.g. `len:5 start:c !contains:e any(end:y end:er)`
+//!
+//! - `key:value` is a predicate, the keys are in `PREDICATE_KEYS`
+//! - `!` in front of anything negates it
+//! - `any(...)` and `all(...)` are groups, the top level acts like `all`
+//! - values run until whitespace or an unmatched `)`, or can be quoted like `wordle:"crane:gy..y
+//!   doubt:..y.."` with `\"` and `\\` as escapes inside the quotes
+
+use crate::{
+    expr::PredicateExpr,
+    pred::{WordPredicate, PREDICATE_KEYS},
+};
+
+/// Where and why a query couldn't be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError {
+    /// index of the offending character, counted in chars
+    pub pos: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} at column {}", self.message, self.pos + 1)
+    }
+}
+
+pub fn parse(s: &str) -> Result<Vec<PredicateExpr>, QueryError> {
+    let mut parser = Parser {
+        chars: s.chars().collect(),
+        pos: 0,
+    };
+    parser.parse_list(None)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn error<T>(&self, pos: usize, message: impl Into<String>) -> Result<T, QueryError> {
+        Err(QueryError {
+            pos,
+            message: message.into(),
+        })
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    /// terms up to the end of the query, or up to the `)` closing the group opened at `open`
+    fn parse_list(&mut self, open: Option<usize>) -> Result<Vec<PredicateExpr>, QueryError> {
+        let mut list = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+
+            match (self.peek(), open) {
+                (None, None) => return Ok(list),
+                (None, Some(open)) => return self.error(open, "( is never closed"),
+                (Some(')'), Some(_)) => {
+                    self.pos += 1;
+                    return Ok(list);
+                }
+                (Some(')'), None) => return self.error(self.pos, ") was never opened"),
+                _ => list.push(self.parse_term()?),
+            }
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<PredicateExpr, QueryError> {
+        let start = self.pos;
+
+        if self.peek() == Some('!') {
+            self.pos += 1;
+            return Ok(PredicateExpr::Not(Box::new(self.parse_term()?)));
+        }
+
+        while self
+            .peek()
+            .is_some_and(|c| !c.is_whitespace() && !"():!\"".contains(c))
+        {
+            self.pos += 1;
+        }
+        let key: String = self.chars[start..self.pos].iter().collect();
+
+        match self.peek() {
+            Some('(') => {
+                let open = self.pos;
+                self.pos += 1;
+                match key.as_str() {
+                    "any" => Ok(PredicateExpr::AnyOf(self.parse_list(Some(open))?)),
+                    "all" => Ok(PredicateExpr::AllOf(self.parse_list(Some(open))?)),
+                    _ => self.error(start, format!("\"{}\" is not any or all", key)),
+                }
+            }
+            Some(':') => {
+                let Some(index) = PREDICATE_KEYS.iter().position(|k| *k == key) else {
+                    return self.error(start, format!("unknown predicate \"{}\"", key));
+                };
+                self.pos += 1;
+
+                let value_start = self.pos;
+                let (value, positions) = self.parse_value()?;
+
+                let mut p = WordPredicate::from_index(index).expect("keys match predicates");
+
+                p.update(&value);
+                if let Some(e) = p.get_error() {
+                    let pos = p
+                        .get_error_pos()
+                        .and_then(|i| positions.get(i).copied())
+                        .unwrap_or(value_start);
+                    return self.error(pos, e);
+                }
+
+                Ok(PredicateExpr::Pred(p))
+            }
+            _ if key.is_empty() => self.error(start, "expected a predicate"),
+            _ => self.error(self.pos, format!("expected : after \"{}\"", key)),
+        }
+    }
+
+    /// the value, and where each of its chars is in the query since quotes and escapes move them
+    fn parse_value(&mut self) -> Result<(String, Vec<usize>), QueryError> {
+        let mut value = String::new();
+        let mut positions = Vec::new();
+
+        if self.peek() == Some('"') {
+            let open = self.pos;
+            self.pos += 1;
+
+            loop {
+                match self.peek() {
+                    None => return self.error(open, "\" is never closed"),
+                    Some('"') => {
+                        self.pos += 1;
+                        return Ok((value, positions));
+                    }
+                    Some('\\') if matches!(self.chars.get(self.pos + 1), Some('"' | '\\')) => {
+                        value.push(self.chars[self.pos + 1]);
+                        positions.push(self.pos + 1);
+                        self.pos += 2;
+                    }
+                    Some(c) => {
+                        value.push(c);
+                        positions.push(self.pos);
+                        self.pos += 1;
+                    }
+                }
+            }
+        }
+
+        // brackets inside the value are fine as long as they match, like in `regex:(ab)+`
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            match c {
+                c if c.is_whitespace() => break,
+                ')' if depth == 0 => break,
+                ')' => depth -= 1,
+                '(' => depth += 1,
+                _ => {}
+            }
+            value.push(c);
+            positions.push(self.pos);
+            self.pos += 1;
+        }
+
+        Ok((value, positions))
+    }
+}
+
+// display ---------------------------------------------------------------------
+
+/// Shows a predicate tree as a query that parses back to the same tree
+pub struct Query<'a>(pub &'a [PredicateExpr]);
+
+impl std::fmt::Display for Query<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, e) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write_expr(f, e)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_expr(f: &mut std::fmt::Formatter, e: &PredicateExpr) -> std::fmt::Result {
+    match e {
+        PredicateExpr::Pred(p) => {
+            write!(f, "{}:", p.key())?;
+            write_value(f, &p.get_string())
+        }
+        PredicateExpr::Not(e) => {
+            write!(f, "!")?;
+            write_expr(f, e)
+        }
+        PredicateExpr::AnyOf(es) => write!(f, "any({})", Query(es)),
+        PredicateExpr::AllOf(es) => write!(f, "all({})", Query(es)),
+    }
+}
+
+fn write_value(f: &mut std::fmt::Formatter, value: &str) -> std::fmt::Result {
+    let needs_quotes = value.is_empty()
+        || value.starts_with('"')
+        || value
+            .chars()
+            .any(|c| c.is_whitespace() || c == '(' || c == ')');
+
+    if !needs_quotes {
+        return write!(f, "{}", value);
+    }
+
+    write!(f, "\"")?;
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            write!(f, "\\")?;
+        }
+        write!(f, "{}", c)?;
+    }
+    write!(f, "\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(s: &str) -> String {
+        let shown = Query(&parse(s).unwrap()).to_string();
+        let again = Query(&parse(&shown).unwrap()).to_string();
+        assert_eq!(shown, again, "{} doesn't parse back the same", shown);
+        shown
+    }
+
+    fn error_pos(s: &str) -> usize {
+        parse(s).unwrap_err().pos
+    }
+
+    #[test]
+    fn plain_query_is_shown_as_written() {
+        let s = "len:5 start:c !contains:e any(end:y end:ch)";
+        assert_eq!(round_trip(s), s);
+    }
+
+    #[test]
+    fn quoted_values_round_trip() {
+        assert_eq!(
+            round_trip(r#"wordle:"crane:gy..y doubt:..y..""#),
+            r#"wordle:"crane:gy..y doubt:..y..""#
+        );
+        assert_eq!(round_trip(r#"end:"\"\\""#), r#"end:"\"\\""#);
+        assert_eq!(round_trip(r#"contains:"""#), r#"contains:"""#);
+
+        let list = parse(r#"end:"\"\\""#).unwrap();
+        let value = list[0].predicate().unwrap().get_string();
+        assert_eq!(value, r#""\"#);
+    }
+
+    #[test]
+    fn values_with_brackets_round_trip() {
+        // matched brackets don't need quotes, but they are quoted when shown
+        let shown = round_trip("regex:(ab)+c any(regex:[()]x all(fullregex:a|b))");
+        assert_eq!(
+            shown,
+            r#"regex:"(ab)+c" any(regex:"[()]x" all(fullregex:a|b))"#
+        );
+
+        let list = parse(&shown).unwrap();
+        assert_eq!(list[0].predicate().unwrap().get_string(), "(ab)+c");
+    }
+
+    #[test]
+    fn unknown_key_points_at_the_key() {
+        assert_eq!(error_pos("len:5 foo:bar"), 6);
+    }
+
+    #[test]
+    fn unclosed_group_points_at_the_bracket() {
+        assert_eq!(error_pos("len:5 any(end:y"), 9);
+        assert_eq!(error_pos("len:5 any(end:y all(start:a)"), 9);
+        assert_eq!(error_pos("len:5)"), 5);
+    }
+
+    #[test]
+    fn unclosed_quote_points_at_the_quote() {
+        assert_eq!(error_pos(r#"len:5 wordle:"crane:gy..y"#), 13);
+    }
+
+    #[test]
+    fn bad_values_point_at_the_value() {
+        assert_eq!(error_pos("start:a len:abc"), 12);
+        assert_eq!(error_pos("regex:(ab"), 6);
+    }
+
+    #[test]
+    fn bad_values_point_inside_the_value() {
+        let e = parse("len:5 pattern:ab[cd").unwrap_err();
+        assert_eq!((e.pos, e.message.as_str()), (16, "[ is never closed"));
+        assert_eq!(error_pos("shape:AB1"), 8);
+        // quotes and escapes are accounted for
+        assert_eq!(error_pos(r#"pattern:"\\""#), 10);
+        assert_eq!(error_pos(r#"wordle:"crane:gy..y doubt:..z..""#), 28);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::prime::letter_to_index;
+
+// points for each tile in an english scrabble set
+const TILE_VALUES: [u32; 26] = [
+    1,  // a
+    3,  // b
+    3,  // c
+    2,  // d
+    1,  // e
+    4,  // f
+    2,  // g
+    4,  // h
+    1,  // i
+    8,  // j
+    5,  // k
+    1,  // l
+    3,  // m
+    1,  // n
+    1,  // o
+    3,  // p
+    10, // q
+    1,  // r
+    1,  // s
+    1,  // t
+    1,  // u
+    4,  // v
+    4,  // w
+    8,  // x
+    4,  // y
+    10, // z
+];
+
+/// the face value of the word's tiles, ignoring any board bonuses
+///
+/// when the `rack` is known, letters it doesn't have a tile for are played with blanks and are
+/// worth nothing. letters other than a-z are worth nothing either way
+pub fn score(word: &str, rack: Option<&str>) -> u32 {
+    let Some(rack) = rack else {
+        return word
+            .chars()
+            .filter_map(letter_to_index)
+            .map(|i| TILE_VALUES[i])
+            .sum();
+    };
+
+    let mut tiles = [0u32; 26];
+    for i in rack.chars().filter_map(letter_to_index) {
+        tiles[i] += 1;
+    }
+
+    // real tiles are never worth less than a blank, so they're always used first
+    let mut total = 0;
+    for i in word.chars().filter_map(letter_to_index) {
+        if tiles[i] > 0 {
+            tiles[i] -= 1;
+            total += TILE_VALUES[i];
+        }
+    }
+    total
+}
+
+/// parses an inclusive range of scores like `10-20`, `10-`, `-8` or just `12`. nothing at all
+/// is no limit, so a new predicate doesn't hide every word
+pub fn parse_score_range(s: &str) -> Result<(u32, u32), String> {
+    if s.trim().is_empty() {
+        return Ok((0, u32::MAX));
+    }
+
+    let parse = |n: &str, default: u32| -> Result<u32, String> {
+        let n = n.trim();
+        if n.is_empty() {
+            Ok(default)
+        } else {
+            n.parse().map_err(|_| format!("\"{}\" is not a score", n))
+        }
+    };
+
+    let (min, max) = match s.split_once('-') {
+        Some((min, max)) => (parse(min, 0)?, parse(max, u32::MAX)?),
+        None => {
+            let n = parse(s, 0)?;
+            (n, n)
+        }
+    };
+
+    if min > max {
+        return Err(format!("{} is more than {}", min, max));
+    }
+    Ok((min, max))
+}
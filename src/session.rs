@@ -0,0 +1,59 @@
+//! The search that was open when the app was last closed, kept in the same `key = value` format as
+//! presets (see `preset`) with the focused pane added:
+//!
+//! ```text
+//! list = ./lists/wordlist.txt
+//! query = len:5 !contains:e
+//! sort = Alphabetical
+//! pane = sorting
+//! ```
+
+use crate::preset::Preset;
+
+/// where the session is kept, next to the lists folder
+pub const SESSION_PATH: &str = "./session.txt";
+
+#[derive(Debug)]
+pub struct Session {
+    pub preset: Preset,
+    pub pane: String,
+}
+
+/// `Ok(None)` if there is no saved session
+pub fn load_session(path: &str) -> Result<Option<Session>, String> {
+    let file = match std::fs::read_to_string(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("couldn't read {}: {}", path, e)),
+    };
+
+    let mut session = Session {
+        preset: Preset::new("session"),
+        pane: String::new(),
+    };
+
+    for (i, line) in file.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("{} line {}: expected key = value", path, i + 1));
+        };
+        let (key, value) = (key.trim(), value.trim());
+
+        if key == "pane" {
+            session.pane = value.to_string();
+        } else if let Err(e) = session.preset.set(key, value) {
+            return Err(format!("{} line {}: {}", path, i + 1, e));
+        }
+    }
+
+    Ok(Some(session))
+}
+
+pub fn save_session(path: &str, session: &Session) -> Result<(), String> {
+    let file = format!("{}pane = {}\n", session.preset.settings(), session.pane);
+    std::fs::write(path, file).map_err(|e| format!("couldn't write {}: {}", path, e))
+}
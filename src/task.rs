@@ -0,0 +1,63 @@
+//! Work done on another thread so the interface keeps up with typing
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::{self, Receiver, Sender},
+    Arc,
+};
+
+/// A thread sending back messages as it works, cancelled when this is dropped
+#[derive(Debug)]
+pub struct Task<M> {
+    cancelled: Arc<AtomicBool>,
+    receiver: Receiver<M>,
+}
+
+impl<M: Send + 'static> Task<M> {
+    pub fn spawn(work: impl FnOnce(Progress<M>) + Send + 'static) -> Self {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+
+        let progress = Progress {
+            cancelled: Arc::clone(&cancelled),
+            sender,
+        };
+        std::thread::spawn(move || work(progress));
+
+        Task {
+            cancelled,
+            receiver,
+        }
+    }
+}
+
+impl<M> Task<M> {
+    /// the next message if one has arrived, never waits
+    pub fn try_recv(&self) -> Option<M> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl<M> Drop for Task<M> {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// The working thread's end of a `Task`
+pub struct Progress<M> {
+    cancelled: Arc<AtomicBool>,
+    sender: Sender<M>,
+}
+
+impl<M> Progress<M> {
+    /// the work should stop as soon as it can, nobody wants the result anymore
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// false once the task was dropped
+    pub fn send(&self, message: M) -> bool {
+        !self.is_cancelled() && self.sender.send(message).is_ok()
+    }
+}
@@ -0,0 +1,321 @@
+//! A prefix tree over the word list, so predicates that fix the first letters or limit which
+//! letters can be used find their candidates without looking at every word
+//!
+//! the words are referred to by their index in the list, and are only candidates: the predicates
+//! still check each of them
+
+use crate::pred::WordPredicate;
+
+#[derive(Debug, Clone, Default)]
+pub struct Trie {
+    /// word indices in alphabetical order, the words under any node are a run of these
+    alpha: Vec<u32>,
+    /// the root is the first node, and the children of each node are next to each other
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    letter: char,
+    /// `alpha[start..end]` are the words starting with the letters leading to this node
+    start: u32,
+    end: u32,
+    /// how many of those end here, they come first
+    ends: u32,
+    first_child: u32,
+    children: u32,
+}
+
+impl Trie {
+    pub fn new(words: &[String]) -> Self {
+        let mut alpha: Vec<u32> = (0..words.len() as u32).collect();
+        alpha.sort_by(|a, b| words[*a as usize].cmp(&words[*b as usize]));
+
+        let mut nodes = vec![Node {
+            letter: '\0',
+            start: 0,
+            end: alpha.len() as u32,
+            ends: 0,
+            first_child: 0,
+            children: 0,
+        }];
+        let mut depths = vec![0];
+
+        // breadth first, so the children of each node are added one after another. a word that
+        // ends sorts before the longer ones, and the rest are grouped by their next letter
+        let mut i = 0;
+        while i < nodes.len() {
+            let (start, end, depth) = (nodes[i].start as usize, nodes[i].end as usize, depths[i]);
+            let letter_at = |k: usize| words[alpha[k] as usize].chars().nth(depth);
+
+            let mut k = start;
+            while k < end && letter_at(k).is_none() {
+                k += 1;
+            }
+            nodes[i].ends = (k - start) as u32;
+            nodes[i].first_child = nodes.len() as u32;
+
+            while let Some(letter) = (k < end).then(|| letter_at(k)).flatten() {
+                let child_start = k;
+                while k < end && letter_at(k) == Some(letter) {
+                    k += 1;
+                }
+                nodes.push(Node {
+                    letter,
+                    start: child_start as u32,
+                    end: k as u32,
+                    ends: 0,
+                    first_child: 0,
+                    children: 0,
+                });
+                depths.push(depth + 1);
+            }
+
+            nodes[i].children = nodes.len() as u32 - nodes[i].first_child;
+            i += 1;
+        }
+
+        Trie { alpha, nodes }
+    }
+
+    fn children(&self, node: &Node) -> &[Node] {
+        let first = node.first_child as usize;
+        &self.nodes[first..first + node.children as usize]
+    }
+
+    /// the words that are exactly the letters leading to `node`
+    fn ending_at(&self, node: &Node) -> &[u32] {
+        &self.alpha[node.start as usize..(node.start + node.ends) as usize]
+    }
+
+    fn under(&self, node: &Node) -> &[u32] {
+        &self.alpha[node.start as usize..node.end as usize]
+    }
+
+    /// the candidates for a predicate, or `None` if it can't be answered from the tree
+    pub fn candidates(&self, p: &WordPredicate) -> Option<Vec<u32>> {
+        match p {
+            WordPredicate::StartsWith(prefix) => Some(self.with_prefix(prefix)),
+            WordPredicate::Pattern {
+                parsed: Ok(pattern),
+                ..
+            } => {
+                let leading = pattern.leading();
+                let exact = pattern.fixed_len().is_some();
+                (leading > 0).then(|| self.by_position(leading, exact, |i, c| pattern.allows(i, c)))
+            }
+            WordPredicate::UsingLetters(letters) => {
+                let mut budget: Vec<(char, u32)> = letters.chars().map(|c| (c, u32::MAX)).collect();
+                Some(self.spelled_with(&mut budget, 0, None))
+            }
+            WordPredicate::ScrabblePlayable(tiles) => {
+                let blanks = tiles.chars().filter(|c| *c == '?').count() as u32;
+                Some(self.spelled_with(&mut letter_budget(tiles), blanks, None))
+            }
+            WordPredicate::SubAnagram {
+                letters,
+                bag: Ok(Some(_)),
+                ..
+            } => Some(self.spelled_with(&mut letter_budget(&letters.to_lowercase()), 0, None)),
+            WordPredicate::Anagram {
+                letters,
+                bag: Ok(Some(_)),
+                ..
+            } => {
+                let len = letters.chars().count();
+                let mut budget = letter_budget(&letters.to_lowercase());
+                Some(self.spelled_with(&mut budget, 0, Some(len)))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn with_prefix(&self, prefix: &str) -> Vec<u32> {
+        let mut node = &self.nodes[0];
+        for c in prefix.chars() {
+            match self.children(node).iter().find(|n| n.letter == c) {
+                Some(child) => node = child,
+                None => return Vec::new(),
+            }
+        }
+        self.under(node).to_vec()
+    }
+
+    /// the words whose first `len` letters are each allowed at their position, only those of
+    /// exactly that length if `exact`
+    pub fn by_position(
+        &self,
+        len: usize,
+        exact: bool,
+        allows: impl Fn(usize, char) -> bool,
+    ) -> Vec<u32> {
+        let mut found = Vec::new();
+        self.by_position_from(&self.nodes[0], 0, len, exact, &allows, &mut found);
+        found
+    }
+
+    fn by_position_from(
+        &self,
+        node: &Node,
+        depth: usize,
+        len: usize,
+        exact: bool,
+        allows: &impl Fn(usize, char) -> bool,
+        found: &mut Vec<u32>,
+    ) {
+        if depth == len {
+            match exact {
+                true => found.extend_from_slice(self.ending_at(node)),
+                false => found.extend_from_slice(self.under(node)),
+            }
+            return;
+        }
+
+        for child in self.children(node) {
+            if allows(depth, child.letter) {
+                self.by_position_from(child, depth + 1, len, exact, allows, found);
+            }
+        }
+    }
+
+    /// the words that can be spelled with the letters in `budget`, each at most as many times as
+    /// it's counted there, and `blanks` standing in for any letter. with `len` only the words
+    /// of that length
+    pub fn spelled_with(
+        &self,
+        budget: &mut [(char, u32)],
+        blanks: u32,
+        len: Option<usize>,
+    ) -> Vec<u32> {
+        let mut found = Vec::new();
+        self.spelled_with_at(&self.nodes[0], 0, budget, blanks, len, &mut found);
+        found
+    }
+
+    fn spelled_with_at(
+        &self,
+        node: &Node,
+        depth: usize,
+        budget: &mut [(char, u32)],
+        blanks: u32,
+        len: Option<usize>,
+        found: &mut Vec<u32>,
+    ) {
+        if len.is_none_or(|len| len == depth) {
+            found.extend_from_slice(self.ending_at(node));
+        }
+        if len == Some(depth) {
+            return;
+        }
+
+        for child in self.children(node) {
+            // a blank can stand for anything later, so the letter itself is always the better use
+            match budget
+                .iter()
+                .position(|(c, n)| *c == child.letter && *n > 0)
+            {
+                Some(i) => {
+                    budget[i].1 -= 1;
+                    self.spelled_with_at(child, depth + 1, budget, blanks, len, found);
+                    budget[i].1 += 1;
+                }
+                None if blanks > 0 => {
+                    self.spelled_with_at(child, depth + 1, budget, blanks - 1, len, found)
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+/// how many of each letter there are, leaving out blanks
+fn letter_budget(letters: &str) -> Vec<(char, u32)> {
+    let mut budget: Vec<(char, u32)> = Vec::new();
+    for c in letters.chars().filter(|c| *c != '?') {
+        match budget.iter_mut().find(|(l, _)| *l == c) {
+            Some((_, n)) => *n += 1,
+            None => budget.push((c, 1)),
+        }
+    }
+    budget
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lists::{Provenance, WordInfo},
+        prime::Signature,
+        query, words,
+    };
+
+    const QUERIES: [&str; 18] = [
+        "start:a",
+        "start:zy",
+        "start:xyzzy",
+        "pattern:c?a??",
+        "pattern:[aeiou]??[^st]",
+        "pattern:un*able",
+        "pattern:?*ing",
+        "pattern:b[^a]*",
+        "using:abc",
+        "using:etaoin",
+        "rack:aeinrst",
+        "rack:quiz??e",
+        "rack:??",
+        "anagram:listen",
+        "anagram:aab",
+        "subanagram:retains",
+        "subanagram:zzz",
+        "subanagram:eerie",
+    ];
+
+    /// checking only the candidates finds the same words as checking every word, for the only
+    /// predicate of each query
+    fn assert_same_as_scan(words: &[String], queries: &[&str]) {
+        let trie = Trie::new(words);
+        let signatures: Vec<Option<Signature>> = words.iter().map(|w| Signature::new(w)).collect();
+
+        for q in queries {
+            let list = query::parse(q).unwrap();
+            let p = list[0].predicate().unwrap();
+            let is_match = |i: &u32| {
+                let info = WordInfo {
+                    from: Provenance {
+                        lists: &[],
+                        mask: 0,
+                    },
+                    frequency: None,
+                    letters: signatures[*i as usize].as_ref(),
+                };
+                p.matches(&words[*i as usize], info)
+            };
+
+            let scan: Vec<u32> = (0..words.len() as u32).filter(is_match).collect();
+            let mut candidates: Vec<u32> = trie
+                .candidates(p)
+                .unwrap_or_else(|| panic!("{} has no candidates", q))
+                .into_iter()
+                .filter(is_match)
+                .collect();
+            candidates.sort_unstable();
+            assert_eq!(scan, candidates, "{}", q);
+        }
+    }
+
+    #[test]
+    fn candidates_match_a_scan_of_the_dictionary() {
+        let words = words::read_list("./lists/dictionary.txt").unwrap().words;
+        assert_same_as_scan(&words, &QUERIES);
+    }
+
+    #[test]
+    fn candidates_match_a_scan_of_words_that_are_prefixes() {
+        let words: Vec<String> = ["a", "aa", "aab", "ab", "aba", "b", "ba", "baa", "éa", "bé"]
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        assert_same_as_scan(&words, &QUERIES);
+        assert_same_as_scan(&words, &["start:é", "pattern:?a", "using:aé", "rack:a?"]);
+    }
+}
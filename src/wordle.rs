@@ -0,0 +1,179 @@
+use crate::pred::InputError;
+
+/// The colour of a single tile in a wordle guess
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Feedback {
+    /// right letter in the right spot
+    Green,
+    /// letter is in the word, but not in this spot
+    Yellow,
+    /// no (more) copies of this letter are in the word
+    Gray,
+}
+
+impl Feedback {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'g' | 'G' => Some(Feedback::Green),
+            'y' | 'Y' => Some(Feedback::Yellow),
+            '.' | '-' | '_' | 'x' | 'X' | 'b' | 'B' => Some(Feedback::Gray),
+            _ => None,
+        }
+    }
+}
+
+/// A guess that was made along with the feedback the game gave for it
+#[derive(Debug, Clone)]
+pub struct WordleGuess {
+    guess: Vec<u8>,
+    feedback: Vec<Feedback>,
+}
+
+impl WordleGuess {
+    /// parses `guess:feedback`, e.g. `crane:gy..y`
+    ///
+    /// feedback uses `g` for green, `y` for yellow and any of `.`, `-`, `_`, `x` or `b` for gray
+    pub fn parse(s: &str) -> Result<Self, InputError> {
+        let Some((guess, feedback)) = s.split_once(':') else {
+            let message = format!("expected guess:feedback in \"{}\"", s);
+            return Err(InputError::new(0, message));
+        };
+
+        if let Some((i, c)) = guess
+            .chars()
+            .enumerate()
+            .find(|(_, c)| !c.is_ascii_alphabetic())
+        {
+            return Err(InputError::new(i, format!("'{}' is not a letter", c)));
+        }
+
+        // the feedback starts after the guess and the :
+        let start = guess.len() + 1;
+        let feedback = feedback
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let message = format!("'{}' is not g, y or .", c);
+                Feedback::from_char(c).ok_or(InputError::new(start + i, message))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if guess.len() != feedback.len() {
+            let message = format!(
+                "\"{}\" has {} letters but {} feedback tiles",
+                guess,
+                guess.len(),
+                feedback.len()
+            );
+            return Err(InputError::new(0, message));
+        }
+
+        Ok(WordleGuess {
+            guess: guess.to_ascii_lowercase().into_bytes(),
+            feedback,
+        })
+    }
+
+    /// whether `answer` would have produced exactly this feedback for this guess
+    ///
+    /// greens are given out first, then yellows from left to right while unmatched copies of the
+    /// letter remain in the answer. so a repeated letter is gray once the answer's copies of it
+    /// are used up, even though the letter is in the word
+    pub fn allows(&self, answer: &str) -> bool {
+        let answer = answer.as_bytes();
+        if answer.len() != self.guess.len() {
+            return false;
+        }
+
+        // copies of each letter in the answer that aren't already accounted for by a green
+        let mut unmatched = [0u8; 26];
+        for (i, (&g, &a)) in self.guess.iter().zip(answer).enumerate() {
+            let green = g == a;
+            if green != (self.feedback[i] == Feedback::Green) {
+                return false;
+            }
+            if !green && a.is_ascii_lowercase() {
+                unmatched[(a - b'a') as usize] += 1;
+            }
+        }
+
+        for (i, &g) in self.guess.iter().enumerate() {
+            if self.feedback[i] == Feedback::Green {
+                continue;
+            }
+
+            let count = &mut unmatched[(g - b'a') as usize];
+            let yellow = *count > 0;
+            if yellow {
+                *count -= 1;
+            }
+            if yellow != (self.feedback[i] == Feedback::Yellow) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// parses any number of guesses separated by whitespace or commas
+pub fn parse_guesses(s: &str) -> Result<Vec<WordleGuess>, InputError> {
+    let mut guesses = Vec::new();
+    // where each row starts, counted in chars
+    let mut start = 0;
+    for row in s.split(|c: char| c.is_whitespace() || c == ',') {
+        if !row.is_empty() {
+            let guess =
+                WordleGuess::parse(row).map_err(|e| InputError::new(start + e.pos, e.message))?;
+            guesses.push(guess);
+        }
+        start += row.chars().count() + 1;
+    }
+    Ok(guesses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allows(guess: &str, answer: &str) -> bool {
+        WordleGuess::parse(guess).unwrap().allows(answer)
+    }
+
+    #[test]
+    fn repeated_letter_is_yellow_once() {
+        // abide has one e, so only the first of speed's two e's is yellow
+        assert!(allows("speed:..y.y", "abide"));
+        assert!(!allows("speed:...yy", "abide"));
+        assert!(!allows("speed:..yyy", "abide"));
+    }
+
+    #[test]
+    fn green_uses_up_the_letter() {
+        // those has one e and it's green, so the other e's are gray
+        assert!(allows("geese:...gg", "those"));
+        assert!(!allows("geese:.y.gg", "those"));
+        assert!(!allows("geese:.yygg", "those"));
+    }
+
+    #[test]
+    fn too_many_yellows() {
+        // elder has two e's and one is green, so only one of the other two can be yellow
+        assert!(allows("eerie:gyy..", "elder"));
+        assert!(!allows("eerie:gyy.y", "elder"));
+        assert!(!allows("eerie:g.y..", "elder"));
+    }
+
+    #[test]
+    fn wrong_length_never_matches() {
+        assert!(!allows("crane:.....", "toe"));
+    }
+
+    #[test]
+    fn parses_several_guesses() {
+        let guesses = parse_guesses("crane:gy..y, doubt:..y..").unwrap();
+        assert_eq!(guesses.len(), 2);
+        assert!(parse_guesses("crane:gy.").is_err());
+        assert!(parse_guesses("cr4ne:.....").is_err());
+    }
+}
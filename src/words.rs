@@ -1,76 +1,732 @@
 use crate::{
-    ord::{WordOrder, ORDER_NAMES},
+    expr::{self, ExprRow, PredicateExpr, GROUP_NAMES},
+    lists::{self, Frequency, ListSource, Provenance, WordInfo},
+    ord::{self, WordOrder, ORDER_NAMES},
     pred::{WordPredicate, PREDICATE_NAMES},
+    prime::Signature,
+    query::{self, Query, QueryError},
+    task::{Progress, Task},
+    trie::Trie,
 };
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::{cell::OnceCell, collections::HashMap, io, ops::Range, sync::Arc};
+
+/// the list loaded on startup
+pub const DEFAULT_FILE_PATH: &str = "./lists/words_alpha.txt";
+
+/// how many words the filtering thread checks between looking for cancellation and reporting
+const FILTER_CHUNK: usize = 4096;
+
+/// how many words are tried to guess how many each predicate matches, see `expr::plan`
+const PLAN_SAMPLE: usize = 512;
+
+/// The words loaded from one or more files, shared with the filtering thread
+///
+/// the words keep the order they were loaded in so the trie built from them stays valid, sorting
+/// only changes `order`. positions are in sorted order, indices in load order
+#[derive(Debug, Default, Clone)]
+pub struct WordList {
+    pub lists: Vec<ListSource>,
+    words: Vec<String>,
+    /// the index of the word at each position
+    order: Vec<u32>,
+    /// the position of the word at each index
+    rank: Vec<u32>,
+    trie: Trie,
+    /// the letters of the word at each index, see `Signature`
+    signatures: Vec<Option<Signature>>,
+    /// the indices of the words grouped by signature, so all the anagrams of a word are together
+    by_letters: Vec<u32>,
+    /// where each group is in `by_letters`
+    anagrams: HashMap<Signature, Range<usize>>,
+    /// the lists each word is in as bits of `lists`, left empty when there is only one
+    sources: HashMap<String, u64>,
+    /// how common each word is, for the lists that say so
+    frequencies: HashMap<String, Frequency>,
+}
 
 #[derive(Debug)]
 pub struct WordFinder {
+    /// the lists that were loaded as they were entered, see `lists`
     pub file_path: String,
-    pub word_list: Vec<String>,
-    pub predicates: Vec<WordPredicate>,
-    pub word_order: WordOrder,
+    pub list: Arc<WordList>,
+    /// every one of these has to match, groups inside allow for other combinations. call
+    /// `invalidate` after changing them directly
+    pub predicates: Vec<PredicateExpr>,
+    /// sort by the first, ties broken by the ones after it
+    pub word_orders: Vec<WordOrder>,
+    /// indices into the list of the words the predicates match, worked out when first needed
+    /// and kept until something changes
+    filtered: OnceCell<Vec<usize>>,
+    /// filtering in the background, see `poll`
+    filtering: Option<FilterJob>,
+    loading: Option<LoadJob>,
+    sorting: Option<SortJob>,
 }
 
-/// this can just be derived, but i want the default word list until i make it user editable and
-/// at least one good predicate until its faster with bigger word lists
+/// this can just be derived, but i want at least one good predicate until its faster with bigger
+/// word lists. the default word list is loaded by the app so it can show any errors
 impl Default for WordFinder {
     fn default() -> Self {
         let mut wf = Self {
             file_path: String::new(),
-            word_list: Vec::new(),
+            list: Arc::default(),
             predicates: Vec::new(),
-            word_order: WordOrder::default(),
+            word_orders: vec![WordOrder::default()],
+            filtered: OnceCell::new(),
+            filtering: None,
+            loading: None,
+            sorting: None,
         };
 
-        wf.load_file("./lists/words.txt");
-        wf.add_predicate(2); // EndsWith
-        wf.update_predicate(0, "ing");
+        wf.add_predicate(&[], 2); // EndsWith
+        wf.update_predicate(&[0], "ing");
 
         wf
     }
 }
 
-impl WordFinder {
-    pub fn load_file(&mut self, file_path: &str) {
-        // do something better here for error handling, just don't want to crash rn
-        if let Ok(file) = std::fs::read_to_string(file_path) {
-            self.file_path = file_path.to_string();
-            self.word_list = file
-                .split_whitespace() // assume one word per line
-                .map(|w| w.to_string().to_lowercase()) // convert to lowercase
-                .filter(|w| w.chars().all(|c| c.is_alphabetic())) // only alphabetic
-                .collect::<Vec<String>>();
+/// What came of successfully loading a word list
+#[derive(Debug, Clone, Copy)]
+pub struct LoadStats {
+    pub words: usize,
+    /// lines that weren't a single alphabetic word
+    pub skipped: usize,
+    /// skipped lines with a count that isn't a whole number
+    pub uncounted: usize,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Spec(String),
+    Read {
+        path: String,
+        source: io::Error,
+    },
+    /// `uncounted` of the skipped lines had a count that wasn't a whole number
+    NoWords {
+        path: String,
+        skipped: usize,
+        uncounted: usize,
+    },
+}
+
+impl LoadStats {
+    /// like `12 lines skipped`, saying how many of them had a count that isn't a whole number
+    pub fn skipped(&self) -> String {
+        match self.uncounted {
+            0 => format!("{} lines skipped", self.skipped),
+            n => format!(
+                "{} lines skipped, {} with a count that isn't a whole number",
+                self.skipped, n
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LoadError::Spec(e) => write!(f, "{}", e),
+            LoadError::Read { path, source } => write!(f, "couldn't read {}: {}", path, source),
+            LoadError::NoWords {
+                path,
+                skipped,
+                uncounted: 0,
+            } => write!(f, "no words in {} ({} lines skipped)", path, skipped),
+            LoadError::NoWords {
+                path,
+                skipped,
+                uncounted,
+            } => write!(
+                f,
+                "no words in {} ({} lines skipped, {} with a count that isn't a whole number)",
+                path, skipped, uncounted
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Read { source, .. } => Some(source),
+            LoadError::Spec(_) | LoadError::NoWords { .. } => None,
+        }
+    }
+}
+
+impl WordList {
+    /// the words in the file, or the combination of several files, see `lists`
+    pub fn load(file_path: &str) -> Result<(Self, LoadStats), LoadError> {
+        let loaded = Self::load_unless(file_path, &|| false)?;
+        Ok(loaded.expect("never cancelled"))
+    }
+
+    /// like `load`, but gives up with `None` between files and steps once `cancelled` says so
+    fn load_unless(
+        file_path: &str,
+        cancelled: &dyn Fn() -> bool,
+    ) -> Result<Option<(Self, LoadStats)>, LoadError> {
+        let specs = lists::parse_spec(file_path).map_err(LoadError::Spec)?;
+
+        let (mut skipped, mut uncounted) = (0, 0);
+        let mut sources = Vec::new();
+        let mut loaded = Vec::new();
+        let mut frequencies: HashMap<String, Frequency> = HashMap::new();
+        for (op, path) in specs {
+            if cancelled() {
+                return Ok(None);
+            }
+            let list = read_list(&path)?;
+            skipped += list.skipped;
+            uncounted += list.uncounted;
+            sources.push(ListSource {
+                path,
+                op,
+                words: list.words.len(),
+            });
+            loaded.push((op, list.words));
+
+            // the most common a word is in any of the lists
+            for (word, frequency) in list.frequencies {
+                frequencies
+                    .entry(word)
+                    .and_modify(|f| *f = frequency.max(*f))
+                    .or_insert(frequency);
+            }
+        }
+
+        let mut list = WordList {
+            lists: sources,
+            sources: HashMap::new(),
+            frequencies,
+            ..WordList::default()
+        };
+        if let [(_, words)] = loaded.as_mut_slice() {
+            list.words = std::mem::take(words);
         } else {
-            self.file_path = String::new();
-            self.word_list = Vec::new();
+            (list.words, list.sources) = lists::combine(&loaded);
+        }
+        list.order = (0..list.words.len() as u32).collect();
+        list.rank = list.order.clone();
+        if cancelled() {
+            return Ok(None);
+        }
+        list.trie = Trie::new(&list.words);
+        if cancelled() {
+            return Ok(None);
+        }
+        list.group_anagrams();
+
+        let stats = LoadStats {
+            words: list.len(),
+            skipped,
+            uncounted,
+        };
+        Ok(Some((list, stats)))
+    }
+
+    pub fn sort(&mut self, orders: &[WordOrder], rack: Option<&str>) {
+        let sorted = self.sorted(orders, rack, &|| false);
+        self.set_sorted(sorted.expect("never cancelled"));
+    }
+
+    /// the order the words would be in, without changing the list so it can be shared meanwhile.
+    /// `None` if `cancelled` said so before it was done
+    fn sorted(
+        &self,
+        orders: &[WordOrder],
+        rack: Option<&str>,
+        cancelled: &dyn Fn() -> bool,
+    ) -> Option<Sorted> {
+        // from the order they were loaded in, so ties don't depend on earlier sorting
+        let mut order: Vec<u32> = (0..self.words.len() as u32).collect();
+        let word = |i: &u32| self.words[*i as usize].as_str();
+        ord::sort_by_orders(&mut order, word, orders, rack, &self.frequencies, cancelled);
+        if cancelled() {
+            return None;
         }
+
+        let mut rank = vec![0; order.len()];
+        for (position, i) in order.iter().enumerate() {
+            rank[*i as usize] = position as u32;
+        }
+        Some(Sorted { order, rank })
+    }
+
+    fn set_sorted(&mut self, sorted: Sorted) {
+        self.order = sorted.order;
+        self.rank = sorted.rank;
+    }
+
+    fn group_anagrams(&mut self) {
+        self.signatures = self.words.iter().map(|w| Signature::new(w)).collect();
+
+        let signatures = &self.signatures;
+        self.by_letters = (0..self.words.len() as u32)
+            .filter(|i| signatures[*i as usize].is_some())
+            .collect();
+        self.by_letters
+            .sort_unstable_by_key(|i| signatures[*i as usize].map(|s| s.counts()));
+
+        let mut start = 0;
+        for end in 1..=self.by_letters.len() {
+            let signature = signatures[self.by_letters[start] as usize];
+            if end == self.by_letters.len()
+                || signatures[self.by_letters[end] as usize] != signature
+            {
+                if let Some(signature) = signature {
+                    self.anagrams.insert(signature, start..end);
+                }
+                start = end;
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// the word at `position` in sorted order
+    pub fn word(&self, position: usize) -> &String {
+        &self.words[self.order[position] as usize]
+    }
+
+    /// the positions of the words that could match going by the trie or the anagram groups, in
+    /// sorted order, or `None` if the whole list has to be checked. only predicates every match
+    /// has to satisfy are used, and of those whichever leaves the fewest words
+    fn candidates(&self, predicates: &[PredicateExpr]) -> Option<Vec<usize>> {
+        let mut required = Vec::new();
+        expr::required(predicates, &mut required);
+
+        let ids = required
+            .into_iter()
+            .filter_map(|p| self.anagrams_of(p).or_else(|| self.trie.candidates(p)))
+            .min_by_key(|ids| ids.len())?;
+
+        let mut positions: Vec<usize> = ids
+            .iter()
+            .map(|i| self.rank[*i as usize] as usize)
+            .collect();
+        positions.sort_unstable();
+        Some(positions)
+    }
+
+    /// the words an anagram predicate can match, straight from their group
+    fn anagrams_of(&self, p: &WordPredicate) -> Option<Vec<u32>> {
+        let WordPredicate::Anagram {
+            bag: Ok(Some(bag)), ..
+        } = p
+        else {
+            return None;
+        };
+        let group = match self.anagrams.get(bag.signature()?) {
+            Some(group) => &self.by_letters[group.clone()],
+            None => &[],
+        };
+        Some(group.to_vec())
+    }
+
+    /// the lists `word` came from
+    pub fn provenance(&self, word: &str) -> Provenance<'_> {
+        let mask = match self.lists.len() {
+            1 => 1,
+            _ => self.sources.get(word).copied().unwrap_or_default(),
+        };
+        Provenance {
+            lists: &self.lists,
+            mask,
+        }
+    }
+
+    /// how common the word is, if any of the lists have a frequency column
+    pub fn frequency(&self, word: &str) -> Option<Frequency> {
+        self.frequencies.get(word).copied()
+    }
+
+    /// what the lists say about the word at `position`
+    pub fn word_info(&self, position: usize) -> WordInfo<'_> {
+        let i = self.order[position] as usize;
+        WordInfo {
+            from: self.provenance(&self.words[i]),
+            frequency: self.frequency(&self.words[i]),
+            letters: self.signatures[i].as_ref(),
+        }
+    }
+
+    /// the predicates in the order they are quickest to check in, going by how many of a sample of
+    /// words spread over the list each one matches
+    fn plan(&self, predicates: &[PredicateExpr]) -> Vec<PredicateExpr> {
+        let n = PLAN_SAMPLE.min(self.len());
+        if n == 0 {
+            return predicates.to_vec();
+        }
+        let sample: Vec<usize> = (0..n).map(|k| k * self.len() / n).collect();
+
+        let pass_rate = |e: &PredicateExpr| {
+            let passed = sample
+                .iter()
+                .filter(|i| e.matches(self.word(**i), self.word_info(**i)))
+                .count();
+            passed as f64 / sample.len() as f64
+        };
+        expr::plan(predicates, &pass_rate)
+    }
+
+    fn is_match(&self, predicates: &[PredicateExpr], position: usize) -> bool {
+        let (word, info) = (self.word(position), self.word_info(position));
+        predicates.iter().all(|e| e.matches(word, info))
+    }
+
+    /// the positions the predicates match among `range` of `base`, or of the whole list, in order.
+    /// with the `parallel` feature the words are split between all the cores
+    fn matching(
+        &self,
+        predicates: &[PredicateExpr],
+        base: Option<&[usize]>,
+        range: Range<usize>,
+    ) -> Vec<usize> {
+        let position = |k: usize| base.map_or(k, |b| b[k]);
+
+        #[cfg(feature = "parallel")]
+        let range = range.into_par_iter();
+
+        range
+            .map(position)
+            .filter(|i| self.is_match(predicates, *i))
+            .collect()
+    }
+}
+
+// background ------------------------------------------------------------------
+
+type Loaded = Result<(WordList, LoadStats), LoadError>;
+
+#[derive(Debug)]
+struct LoadJob {
+    task: Task<Loaded>,
+    /// what the list is being sorted by, in case it changes before loading is done
+    orders: Vec<WordOrder>,
+    rack: Option<String>,
+}
+
+/// `WordList::order` and `rank` for a new order
+#[derive(Debug)]
+struct Sorted {
+    order: Vec<u32>,
+    rank: Vec<u32>,
+}
+
+#[derive(Debug)]
+struct SortJob {
+    task: Task<Sorted>,
+    /// the result, kept until no other thread is using the list so it can be changed in place
+    done: Option<Sorted>,
+}
+
+#[derive(Debug)]
+enum FilterMessage {
+    /// matches among the next words checked, in list order
+    Found {
+        checked: usize,
+        found: Vec<usize>,
+    },
+    /// the trie ruled out all but this many words before any were checked
+    Narrowed {
+        total: usize,
+    },
+    Done,
+}
+
+#[derive(Debug)]
+struct FilterJob {
+    task: Task<FilterMessage>,
+    /// the indices being filtered, or the whole list
+    base: Option<Arc<Vec<usize>>>,
+    found: Vec<usize>,
+    checked: usize,
+    total: usize,
+}
+
+fn filter_in_background(
+    list: Arc<WordList>,
+    predicates: Vec<PredicateExpr>,
+    base: Option<Arc<Vec<usize>>>,
+    progress: Progress<FilterMessage>,
+) {
+    let predicates = list.plan(&predicates);
+    let base = base.or_else(|| {
+        let candidates = list.candidates(&predicates)?;
+        progress.send(FilterMessage::Narrowed {
+            total: candidates.len(),
+        });
+        Some(Arc::new(candidates))
+    });
+    let total = base.as_ref().map_or(list.len(), |b| b.len());
+
+    for start in (0..total).step_by(FILTER_CHUNK) {
+        let end = total.min(start + FILTER_CHUNK);
+        let found = list.matching(
+            &predicates,
+            base.as_deref().map(|b| b.as_slice()),
+            start..end,
+        );
+
+        if !progress.send(FilterMessage::Found {
+            checked: end,
+            found,
+        }) {
+            return;
+        }
+    }
+
+    progress.send(FilterMessage::Done);
+}
+
+impl WordFinder {
+    /// replaces the word list with the one in the file, or the combination of several files (see
+    /// `lists`), keeping the current one if any of them fail
+    pub fn load_file(&mut self, file_path: &str) -> Result<LoadStats, LoadError> {
+        let (mut list, stats) = WordList::load(file_path)?;
+        list.sort(&self.word_orders, self.rack());
+        self.set_list(list);
+        Ok(stats)
+    }
+
+    /// like `load_file` but on another thread, `poll` says when it's done
+    pub fn start_load(&mut self, file_path: &str) {
+        let file_path = file_path.to_string();
+        let orders = self.word_orders.clone();
+        let rack = self.rack().map(|r| r.to_string());
+
+        let (task_orders, task_rack) = (orders.clone(), rack.clone());
+        let task = Task::spawn(move |progress| {
+            let cancelled = || progress.is_cancelled();
+            let loaded = match WordList::load_unless(&file_path, &cancelled) {
+                Ok(Some((mut list, stats))) => {
+                    let Some(sorted) = list.sorted(&task_orders, task_rack.as_deref(), &cancelled)
+                    else {
+                        return;
+                    };
+                    list.set_sorted(sorted);
+                    Ok((list, stats))
+                }
+                Ok(None) => return,
+                Err(e) => Err(e),
+            };
+            progress.send(loaded);
+        });
+
+        self.loading = Some(LoadJob { task, orders, rack });
+    }
+
+    fn set_list(&mut self, list: WordList) {
+        self.file_path = lists::write_spec(&list.lists);
+        self.list = Arc::new(list);
+        // any sorting was of the old list
+        self.sorting = None;
+        self.invalidate();
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loading.is_some()
+    }
+
+    /// whether anything is still happening in the background
+    pub fn is_busy(&self) -> bool {
+        self.loading.is_some() || self.filtering.is_some() || self.sorting.is_some()
+    }
+
+    /// takes in whatever the background threads have done since the last call, starting to
+    /// filter if the results are out of date. returns how loading went once it's done
+    pub fn poll(&mut self) -> Option<Result<LoadStats, LoadError>> {
+        let mut loaded = None;
+
+        if let Some(result) = self.loading.as_ref().and_then(|job| job.task.try_recv()) {
+            let job = self.loading.take().expect("just received from it");
+            loaded = Some(result.map(|(list, stats)| {
+                self.set_list(list);
+                // sort again if the orders changed while it was loading
+                let rack = self.rack().map(|r| r.to_string());
+                if job.orders != self.word_orders || job.rack != rack {
+                    self.sort();
+                }
+                stats
+            }));
+        }
+
+        if let Some(job) = &mut self.filtering {
+            while let Some(message) = job.task.try_recv() {
+                match message {
+                    FilterMessage::Found { checked, found } => {
+                        job.checked = checked;
+                        job.found.extend(found);
+                    }
+                    FilterMessage::Narrowed { total } => job.total = total,
+                    FilterMessage::Done => {
+                        let found = std::mem::take(&mut job.found);
+                        self.filtered = OnceCell::from(found);
+                        self.filtering = None;
+                        break;
+                    }
+                }
+            }
+        }
+
+        if let Some(job) = &mut self.sorting {
+            if job.done.is_none() {
+                job.done = job.task.try_recv();
+            }
+        }
+        let sorted = self.sorting.as_ref().is_some_and(|job| job.done.is_some());
+        if sorted {
+            // the cache holds positions in the list
+            self.invalidate();
+            // the filtering thread lets go of the list soon after it's cancelled, copying the
+            // whole list to avoid waiting for it would take longer
+            if let Some(list) = Arc::get_mut(&mut self.list) {
+                let job = self.sorting.take().expect("checked above");
+                list.set_sorted(job.done.expect("checked above"));
+            }
+        }
+
+        let waiting = self.sorting.as_ref().is_some_and(|job| job.done.is_some());
+        if self.filtered.get().is_none() && self.filtering.is_none() && !waiting {
+            self.start_filter(None);
+        }
+
+        loaded
+    }
+
+    fn start_filter(&mut self, base: Option<Arc<Vec<usize>>>) {
+        let list = Arc::clone(&self.list);
+        let predicates = self.predicates.clone();
+        let total = base.as_ref().map_or(list.len(), |b| b.len());
+
+        let task_base = base.clone();
+        let task = Task::spawn(move |progress| {
+            filter_in_background(list, predicates, task_base, progress)
+        });
+
+        self.filtering = Some(FilterJob {
+            task,
+            base,
+            found: Vec::new(),
+            checked: 0,
+            total,
+        });
+    }
+
+    pub fn lists(&self) -> &[ListSource] {
+        &self.list.lists
+    }
+
+    pub fn provenance(&self, word: &str) -> Provenance<'_> {
+        self.list.provenance(word)
+    }
+
+    pub fn frequency(&self, word: &str) -> Option<Frequency> {
+        self.list.frequency(word)
     }
 
     // predicates --------------------------------------------------------------
 
+    // predicates are addressed by their path in the expression tree, see `expr`
+
     pub fn iter_predicate_names(&self) -> impl Iterator<Item = &&str> {
-        PREDICATE_NAMES.iter()
+        PREDICATE_NAMES.iter().chain(GROUP_NAMES.iter())
+    }
+
+    pub fn predicate_rows(&self) -> Vec<ExprRow<'_>> {
+        expr::rows(&self.predicates)
     }
 
-    pub fn add_predicate(&mut self, index: usize) {
-        if let Some(p) = WordPredicate::from_index(index) {
-            self.predicates.push(p);
+    /// adds a new predicate or group to the end of the group at `group`
+    pub fn add_predicate(&mut self, group: &[usize], index: usize) {
+        let rack = self.rack().map(|r| r.to_string());
+        if let (Some(e), Some(list)) = (
+            PredicateExpr::from_index(index),
+            expr::group_mut(&mut self.predicates, group),
+        ) {
+            list.push(e);
+            self.invalidate();
         }
+        self.rack_changed(rack);
     }
 
-    pub fn get_predicate_string(&self, index: usize) -> String {
-        // TODO: handle out of bounds
-        self.predicates[index].get_string()
+    pub fn get_predicate_string(&self, path: &[usize]) -> String {
+        expr::get(&self.predicates, path)
+            .and_then(|e| e.predicate())
+            .map(|p| p.get_string())
+            .unwrap_or_default()
     }
 
-    pub fn update_predicate(&mut self, index: usize, s: &str) {
-        if let Some(p) = self.predicates.get_mut(index) {
-            p.update(s);
+    /// when the edit only rules words out, the words that matched before are filtered again
+    /// instead of the whole list
+    pub fn update_predicate(&mut self, path: &[usize], s: &str) {
+        let rack = self.rack().map(|r| r.to_string());
+        let negated = expr::is_negated(&self.predicates, path);
+        let Some(p) = expr::get_mut(&mut self.predicates, path).and_then(|e| e.predicate_mut())
+        else {
+            return;
+        };
+
+        let old = p.clone();
+        p.update(s);
+        let narrows = !negated && p.narrows(&old);
+
+        // the words to filter again, either the last results or what the unfinished filtering
+        // was going through
+        let base = match (self.filtered.take(), self.filtering.take()) {
+            (Some(filtered), _) => Some(Some(Arc::new(filtered))),
+            (None, Some(job)) => Some(job.base),
+            (None, None) => None,
+        };
+
+        match base {
+            Some(base) if narrows => self.start_filter(base),
+            _ => self.invalidate(),
         }
+        self.rack_changed(rack);
     }
 
-    pub fn remove_predicate(&mut self, index: usize) {
-        self.predicates.remove(index);
+    pub fn negate_predicate(&mut self, path: &[usize]) {
+        let rack = self.rack().map(|r| r.to_string());
+        if let Some(e) = expr::get_mut(&mut self.predicates, path) {
+            e.negate();
+            self.invalidate();
+        }
+        self.rack_changed(rack);
+    }
+
+    pub fn toggle_group(&mut self, path: &[usize]) {
+        if let Some(e) = expr::get_mut(&mut self.predicates, path) {
+            e.toggle_group();
+            self.invalidate();
+        }
+    }
+
+    pub fn remove_predicate(&mut self, path: &[usize]) {
+        let rack = self.rack().map(|r| r.to_string());
+        expr::remove(&mut self.predicates, path);
+        self.invalidate();
+        self.rack_changed(rack);
+    }
+
+    /// the predicates written in the query language, see `query`
+    pub fn get_query(&self) -> String {
+        Query(&self.predicates).to_string()
+    }
+
+    /// replaces all the predicates with the ones in the query, unless it doesn't parse
+    pub fn set_query(&mut self, s: &str) -> Result<(), QueryError> {
+        let rack = self.rack().map(|r| r.to_string());
+        self.predicates = query::parse(s)?;
+        self.invalidate();
+        self.rack_changed(rack);
+        Ok(())
     }
 
     // word order --------------------------------------------------------------
@@ -79,21 +735,229 @@ impl WordFinder {
         ORDER_NAMES.iter()
     }
 
+    /// replaces all the orders with just this one
     pub fn set_order(&mut self, index: usize) {
         if let Some(o) = WordOrder::from_index(index) {
-            self.word_order = o;
+            self.word_orders = vec![o];
+        }
+    }
+
+    /// replaces all the orders with the ones at `indices` of `ORDER_NAMES`, in that order of
+    /// priority. a random order gets `seed` if it's given. like the other orders it doesn't sort,
+    /// for when a list is about to be loaded anyway
+    pub fn set_orders(&mut self, indices: &[usize], seed: Option<u64>) {
+        self.word_orders.clear();
+        for index in indices {
+            self.add_order(*index);
+        }
+        for o in self.word_orders.iter_mut() {
+            if let (WordOrder::Random(old), Some(seed)) = (o, seed) {
+                *old = seed;
+            }
+        }
+    }
+
+    /// adds a tie breaker after the current orders, unless that kind of order is already used
+    pub fn add_order(&mut self, index: usize) {
+        if let Some(o) = WordOrder::from_index(index) {
+            if !self.word_orders.iter().any(|w| w.same_kind(&o)) {
+                self.word_orders.push(o);
+            }
+        }
+    }
+
+    pub fn remove_order(&mut self, index: usize) {
+        if let Some(o) = WordOrder::from_index(index) {
+            self.word_orders.retain(|w| !w.same_kind(&o));
         }
     }
 
+    /// where the order at `index` of `ORDER_NAMES` is in the current orders, if it's used
+    pub fn order_position(&self, index: usize) -> Option<usize> {
+        let o = WordOrder::from_index(index)?;
+        self.word_orders.iter().position(|w| w.same_kind(&o))
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.word_orders.iter().find_map(|o| match o {
+            WordOrder::Random(seed) => Some(*seed),
+            _ => None,
+        })
+    }
+
+    /// picks a new seed if there is a random order, and shuffles with it
+    pub fn reroll_seed(&mut self) {
+        if self.seed().is_some() {
+            self.set_seed(ord::new_seed());
+        }
+    }
+
+    pub fn set_seed(&mut self, new_seed: u64) {
+        for o in self.word_orders.iter_mut() {
+            if let WordOrder::Random(seed) = o {
+                *seed = new_seed;
+            }
+        }
+        self.sort();
+    }
+
+    /// sorts by the current orders on another thread, the words stay in their old order until
+    /// `poll` finds it done. a sort still running when this is called again is given up
     pub fn sort(&mut self) {
-        self.word_list.sort_by(|a, b| self.word_order.cmp(a, b));
+        let list = Arc::clone(&self.list);
+        let orders = self.word_orders.clone();
+        let rack = self.rack().map(|r| r.to_string());
+
+        let task = Task::spawn(move |progress| {
+            let cancelled = || progress.is_cancelled();
+            if let Some(sorted) = list.sorted(&orders, rack.as_deref(), &cancelled) {
+                progress.send(sorted);
+            }
+        });
+        self.sorting = Some(SortJob { task, done: None });
     }
 
-    // output
+    /// the tiles of the first top level scrabble playable predicate, if there is one
+    pub fn rack(&self) -> Option<&str> {
+        self.predicates.iter().find_map(|e| match e {
+            PredicateExpr::Pred(WordPredicate::ScrabblePlayable(tiles)) => Some(tiles.as_str()),
+            _ => None,
+        })
+    }
 
-    pub fn iter_filtered(&self) -> impl Iterator<Item = &String> {
-        self.word_list
+    /// sorts again if the predicates changed the rack from `old` while the order depends on it
+    fn rack_changed(&mut self, old: Option<String>) {
+        let scored = self
+            .word_orders
             .iter()
-            .filter(move |word| self.predicates.iter().all(|f| f.matches(word)))
+            .any(|o| matches!(o, WordOrder::Score | WordOrder::RevScore));
+        if scored && self.rack() != old.as_deref() {
+            self.sort();
+        }
+    }
+
+    // output
+
+    /// forgets the matches and stops any filtering, for when the list or predicates changed
+    pub fn invalidate(&mut self) {
+        self.filtered.take();
+        self.filtering = None;
+    }
+
+    /// all the matches, filtering right here if that hasn't been done yet
+    pub fn iter_filtered(&self) -> impl ExactSizeIterator<Item = &String> {
+        let filtered = self.filtered.get_or_init(|| {
+            let predicates = self.list.plan(&self.predicates);
+            match self.list.candidates(&self.predicates) {
+                Some(candidates) => {
+                    self.list
+                        .matching(&predicates, Some(&candidates), 0..candidates.len())
+                }
+                None => self.list.matching(&predicates, None, 0..self.list.len()),
+            }
+        });
+        filtered.iter().map(|i| self.list.word(*i))
+    }
+
+    /// the matches found so far without waiting for the filtering thread, see `poll`
+    pub fn found(&self) -> impl ExactSizeIterator<Item = &String> {
+        let found: &[usize] = match (self.filtered.get(), &self.filtering) {
+            (Some(filtered), _) => filtered,
+            (None, Some(job)) => &job.found,
+            (None, None) => &[],
+        };
+        found.iter().map(|i| self.list.word(*i))
     }
+
+    /// how many words the filtering thread has checked out of how many, while it's running
+    pub fn filter_progress(&self) -> Option<(usize, usize)> {
+        self.filtering.as_ref().map(|job| (job.checked, job.total))
+    }
+}
+
+/// The contents of a word list file
+#[derive(Debug)]
+pub struct ListWords {
+    pub words: Vec<String>,
+    /// from the optional second column
+    pub frequencies: HashMap<String, Frequency>,
+    /// lines that weren't a single alphabetic word, possibly followed by a count
+    pub skipped: usize,
+    /// skipped words followed by a number that isn't a whole number, like a decimal frequency
+    pub uncounted: usize,
+}
+
+/// the words in a file, one per line, each optionally followed by how many times it's used like
+/// `the<TAB>23135851162`. a space or comma works as well as a tab. the count has to be a whole
+/// number, so lines with decimal frequencies are skipped
+///
+/// a first line like `word,rank` makes the second column the rank instead, from 1 for the most
+/// common word
+pub fn read_list(path: &str) -> Result<ListWords, LoadError> {
+    let file = std::fs::read_to_string(path).map_err(|source| LoadError::Read {
+        path: path.to_string(),
+        source,
+    })?;
+
+    let mut ranked = false;
+    let mut list = ListWords {
+        words: Vec::new(),
+        frequencies: HashMap::new(),
+        skipped: 0,
+        uncounted: 0,
+    };
+
+    for line in file.lines().map(|line| line.trim()) {
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut fields = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|f| !f.is_empty());
+        let (Some(word), count, None) = (fields.next(), fields.next(), fields.next()) else {
+            list.skipped += 1;
+            continue;
+        };
+
+        if list.words.is_empty() && !ranked && count.is_some_and(|c| c.eq_ignore_ascii_case("rank"))
+        {
+            ranked = true;
+            continue;
+        }
+
+        // only alphabetic, and a header line like `word,count` doesn't count as a word
+        if !word.chars().all(|c| c.is_alphabetic()) {
+            list.skipped += 1;
+            continue;
+        }
+        let frequency = match count.map(|c| c.parse::<u64>()) {
+            None => None,
+            Some(Ok(n)) if ranked => Some(Frequency::Rank(n)),
+            Some(Ok(n)) => Some(Frequency::Count(n)),
+            Some(Err(_)) => {
+                list.skipped += 1;
+                if count.is_some_and(|c| c.parse::<f64>().is_ok()) {
+                    list.uncounted += 1;
+                }
+                continue;
+            }
+        };
+
+        let word = word.to_lowercase();
+        if let Some(frequency) = frequency {
+            list.frequencies.insert(word.clone(), frequency);
+        }
+        list.words.push(word);
+    }
+
+    if list.words.is_empty() {
+        return Err(LoadError::NoWords {
+            path: path.to_string(),
+            skipped: list.skipped,
+            uncounted: list.uncounted,
+        });
+    }
+
+    Ok(list)
 }